@@ -1,7 +1,463 @@
-use std::env;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use link::LinkConfig;
 
 const USE_LINUX_PAM_ENV_VAR: &str = "USE_LINUX_PAM";
 const USE_OPENPAM_ENV_VAR: &str = "USE_OPENPAM";
+const PAM_LIB_DIR_ENV_VAR: &str = "PAM_LIB_DIR";
+const PAM_LIB_NAME_ENV_VAR: &str = "PAM_LIB_NAME";
+const PAM_STATIC_ENV_VAR: &str = "PAM_STATIC";
+const SYSROOT_ENV_VAR: &str = "PKG_CONFIG_SYSROOT_DIR";
+/// Replaced by `PAM_LIB_DIR` and `PAM_LIB_NAME`, but still accepted
+const LEGACY_PAM_PATH_ENV_VAR: &str = "PAM_PATH";
+
+#[cfg(feature = "vendored")]
+mod vendored {
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Overrides the location of the Linux-PAM source tree, which is the `vendor/linux-pam`
+    /// submodule by default
+    pub const LINUX_PAM_SRC_ENV_VAR: &str = "LINUX_PAM_SRC";
+
+    /// The sources of `libpam` relative to the `libpam` directory of the Linux-PAM tree
+    const LIBPAM_SOURCES: &[&str] = &[
+        "pam_account.c",
+        "pam_audit.c",
+        "pam_auth.c",
+        "pam_data.c",
+        "pam_delay.c",
+        "pam_dispatch.c",
+        "pam_dynamic.c",
+        "pam_end.c",
+        "pam_env.c",
+        "pam_get_authtok.c",
+        "pam_handlers.c",
+        "pam_item.c",
+        "pam_misc.c",
+        "pam_modutil_check_user.c",
+        "pam_modutil_cleanup.c",
+        "pam_modutil_getgrgid.c",
+        "pam_modutil_getgrnam.c",
+        "pam_modutil_getlogin.c",
+        "pam_modutil_getpwnam.c",
+        "pam_modutil_getpwuid.c",
+        "pam_modutil_getspnam.c",
+        "pam_modutil_ingroup.c",
+        "pam_modutil_ioloop.c",
+        "pam_modutil_priv.c",
+        "pam_modutil_sanitize.c",
+        "pam_modutil_searchkey.c",
+        "pam_password.c",
+        "pam_prelude.c",
+        "pam_session.c",
+        "pam_start.c",
+        "pam_strerror.c",
+        "pam_syslog.c",
+        "pam_vprompt.c",
+    ];
+
+    /// The sources of `libpam_misc` relative to the `libpam_misc` directory of the Linux-PAM tree
+    #[cfg(feature = "misc")]
+    const LIBPAM_MISC_SOURCES: &[&str] = &["help_env.c", "misc_conv.c"];
+
+    /// The sources of `libpamc` relative to the `libpamc` directory of the Linux-PAM tree
+    #[cfg(feature = "pamc")]
+    const LIBPAMC_SOURCES: &[&str] = &["pamc_client.c", "pamc_converse.c", "pamc_load.c"];
+
+    /// Overrides the directory that libpam loads the service modules from
+    pub const PAM_MODULE_PATH_ENV_VAR: &str = "PAM_MODULE_PATH";
+
+    /// Replacement for the `config.h` that is normally generated by `configure`, without the
+    /// `_PAM_ISA` of the module directory
+    const CONFIG_H: &str = r#"
+#define PACKAGE "Linux-PAM"
+#define UNUSED __attribute__ ((unused))
+#define HAVE_GETPWNAM_R 1
+#define HAVE_GETPWUID_R 1
+#define HAVE_GETGRNAM_R 1
+#define HAVE_GETGRGID_R 1
+#define HAVE_GETSPNAM_R 1
+#define HAVE_EXPLICIT_BZERO 1
+#define HAVE_SYS_FSUID_H 1
+"#;
+
+    /// Build libpam from the sources and return the version of the source tree.
+    pub fn build() -> Option<String> {
+        println!("cargo:rerun-if-env-changed={LINUX_PAM_SRC_ENV_VAR}");
+
+        let src_dir = env::var_os(LINUX_PAM_SRC_ENV_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+                    .join("vendor")
+                    .join("linux-pam")
+            });
+        let libpam_dir = src_dir.join("libpam");
+
+        if !libpam_dir.is_dir() {
+            panic!(
+                "Failed to find the Linux-PAM sources in `{}`. In a git checkout, fetch them with \
+                 `git submodule update --init`, or point `{LINUX_PAM_SRC_ENV_VAR}` to a Linux-PAM \
+                 source tree.",
+                src_dir.display()
+            );
+        }
+
+        let module_dir = module_dir();
+        // `$ISA` in a module path is replaced with the module directory relative to the default
+        // one, like `configure` does for the library directory
+        let lib_name = Path::new(&module_dir)
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .unwrap_or("lib");
+        let config_h = format!("{CONFIG_H}#define _PAM_ISA \"../../{lib_name}/security\"\n");
+
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+        fs::write(out_dir.join("config.h"), config_h).expect("Failed to write config.h");
+
+        // libpam_misc depends on libpam, so it has to come first when linking
+        #[cfg(feature = "misc")]
+        {
+            let misc_dir = src_dir.join("libpam_misc");
+
+            let mut build = cc::Build::new();
+            build
+                .include(&out_dir)
+                .include(misc_dir.join("include"))
+                .include(libpam_dir.join("include"))
+                .include(src_dir.join("libpamc").join("include"))
+                .define("_GNU_SOURCE", None)
+                .define("HAVE_CONFIG_H", None)
+                .warnings(false);
+
+            for source in LIBPAM_MISC_SOURCES {
+                let path = misc_dir.join(source);
+                println!("cargo:rerun-if-changed={}", path.display());
+                build.file(path);
+            }
+
+            build.compile("pam_misc");
+        }
+
+        #[cfg(feature = "pamc")]
+        {
+            let pamc_dir = src_dir.join("libpamc");
+
+            let mut build = cc::Build::new();
+            build
+                .include(&out_dir)
+                .include(&pamc_dir)
+                .include(pamc_dir.join("include"))
+                .define("_GNU_SOURCE", None)
+                .define("HAVE_CONFIG_H", None)
+                .warnings(false);
+
+            for source in LIBPAMC_SOURCES {
+                let path = pamc_dir.join(source);
+                println!("cargo:rerun-if-changed={}", path.display());
+                build.file(path);
+            }
+
+            build.compile("pamc");
+        }
+
+        let mut build = cc::Build::new();
+        build
+            .include(&out_dir)
+            .include(&libpam_dir)
+            .include(libpam_dir.join("include"))
+            .include(src_dir.join("libpamc").join("include"))
+            .define("_GNU_SOURCE", None)
+            .define("HAVE_CONFIG_H", None)
+            .define("LIBPAM_COMPILE", None)
+            .define("PAM_DYNAMIC", None)
+            .define("DEFAULT_MODULE_PATH", format!("\"{module_dir}/\"").as_str())
+            .define("SYSCONFDIR", "\"/etc\"")
+            .warnings(false);
+
+        for source in LIBPAM_SOURCES {
+            let path = libpam_dir.join(source);
+            println!("cargo:rerun-if-changed={}", path.display());
+            build.file(path);
+        }
+
+        // This emits the `cargo:rustc-link-lib=static=pam` directive
+        build.compile("pam");
+
+        // libpam still loads the service modules at runtime
+        println!("cargo:rustc-link-lib=dl");
+        println!("cargo:include={}", libpam_dir.join("include").display());
+
+        version(&src_dir)
+    }
+
+    /// The directory of the service modules on the target, without a trailing slash.
+    ///
+    /// `PAM_MODULE_PATH` takes precedence. Otherwise, this is the first of the module directories
+    /// of Debian multiarch, lib64 and plain lib systems for the target that contains `pam_unix.so`,
+    /// looked up in the sysroot when one is set. Without a match, it is `/lib/security`.
+    fn module_dir() -> String {
+        if let Some(dir) = super::target_env_var(PAM_MODULE_PATH_ENV_VAR) {
+            return dir.trim_end_matches('/').to_string();
+        }
+
+        let multiarch = super::multiarch();
+        let mut candidates = vec![
+            format!("/lib/{multiarch}/security"),
+            format!("/usr/lib/{multiarch}/security"),
+        ];
+        if env::var("CARGO_CFG_TARGET_POINTER_WIDTH").as_deref() == Ok("64") {
+            candidates.push("/usr/lib64/security".to_string());
+            candidates.push("/lib64/security".to_string());
+        }
+        candidates.push("/usr/lib/security".to_string());
+        candidates.push("/lib/security".to_string());
+
+        let root = super::sysroot().unwrap_or_else(|| PathBuf::from("/"));
+        if let Some(dir) = candidates
+            .into_iter()
+            .find(|dir| root.join(&dir[1..]).join("pam_unix.so").exists())
+        {
+            return dir;
+        }
+
+        println!(
+            "cargo:warning=Found no PAM modules for the target. They are loaded from \
+             `/lib/security`, unless `{PAM_MODULE_PATH_ENV_VAR}` is set."
+        );
+        "/lib/security".to_string()
+    }
+
+    /// Read the version from `meson.build`, or from `configure.ac` for older releases.
+    fn version(src_dir: &Path) -> Option<String> {
+        if let Ok(meson) = fs::read_to_string(src_dir.join("meson.build")) {
+            // version: '1.6.0',
+            let rest = &meson[meson.find("version:")? + "version:".len()..];
+            let rest = &rest[rest.find('\'')? + 1..];
+            return Some(rest[..rest.find('\'')?].to_string());
+        }
+
+        // AC_INIT([Linux-PAM], [1.5.3], ...)
+        let configure = fs::read_to_string(src_dir.join("configure.ac")).ok()?;
+        let rest = &configure[configure.find("AC_INIT(")?..];
+        let rest = &rest[rest.find("],")? + 2..];
+        let rest = &rest[rest.find('[')? + 1..];
+        Some(rest[..rest.find(']')?].to_string())
+    }
+}
+
+#[path = "build/symbols.rs"]
+mod symbols;
+
+#[path = "build/link.rs"]
+mod link;
+
+/// Read an environment variable, preferring the variant for the target such as
+/// `PAM_LIB_DIR_aarch64_unknown_linux_gnu`.
+fn target_env_var(name: &str) -> Option<String> {
+    let target = env::var("TARGET").unwrap_or_default().replace('-', "_");
+    let targeted = format!("{name}_{target}");
+    println!("cargo:rerun-if-env-changed={targeted}");
+    println!("cargo:rerun-if-env-changed={name}");
+
+    env::var(&targeted).or_else(|_| env::var(name)).ok()
+}
+
+fn is_cross_compiling() -> bool {
+    env::var("TARGET").ok() != env::var("HOST").ok()
+}
+
+/// The Debian-style multiarch name of the target, which leaves out the vendor, like
+/// `aarch64-linux-gnu`
+fn multiarch() -> String {
+    let target = env::var("TARGET").unwrap_or_default();
+    let parts: Vec<&str> = target.split('-').collect();
+    match parts[..] {
+        [arch, _vendor, os, env] => format!("{arch}-{os}-{env}"),
+        _ => target.clone(),
+    }
+}
+
+fn sysroot() -> Option<PathBuf> {
+    target_env_var(SYSROOT_ENV_VAR)
+        .filter(|sysroot| !sysroot.is_empty())
+        .map(PathBuf::from)
+}
+
+impl LinkConfig {
+    /// The configuration from `PAM_LIB_DIR` and `PAM_LIB_NAME`, or `None` when pkg-config should
+    /// find libpam.
+    fn from_env(statik: bool) -> Option<Self> {
+        let dir = target_env_var(PAM_LIB_DIR_ENV_VAR).map(PathBuf::from);
+        let name = target_env_var(PAM_LIB_NAME_ENV_VAR);
+
+        let legacy = env::var(LEGACY_PAM_PATH_ENV_VAR).ok().map(|path| {
+            println!(
+                "cargo:warning=`{LEGACY_PAM_PATH_ENV_VAR}` is deprecated. Use \
+                 `{PAM_LIB_DIR_ENV_VAR}` and `{PAM_LIB_NAME_ENV_VAR}` instead."
+            );
+            Self::from_path(Path::new(&path))
+        });
+
+        if dir.is_none() && name.is_none() {
+            return legacy.map(|legacy| Self {
+                statik: statik || legacy.statik,
+                ..legacy
+            });
+        }
+
+        Some(Self {
+            dir: dir.or_else(|| legacy.as_ref().and_then(|legacy| legacy.dir.clone())),
+            name: name
+                .or_else(|| legacy.as_ref().map(|legacy| legacy.name.clone()))
+                .unwrap_or_else(|| "pam".to_string()),
+            statik: statik || legacy.is_some_and(|legacy| legacy.statik),
+        })
+    }
+}
+
+/// The libpam that is built against, which is inspected to detect its implementation and version.
+struct TargetLibrary {
+    name: String,
+    statik: bool,
+    /// The file of the library, when it was found
+    path: Option<PathBuf>,
+}
+
+impl TargetLibrary {
+    /// Look for the library in the given directories, and in the usual directories of the sysroot.
+    fn locate(name: &str, statik: bool, dirs: &[PathBuf]) -> Self {
+        let sysroot = sysroot();
+
+        let mut dirs = dirs.to_vec();
+        if let Some(sysroot) = &sysroot {
+            let multiarch = multiarch();
+            for dir in ["lib", "usr/lib", "lib64", "usr/lib64", "usr/local/lib"] {
+                dirs.push(sysroot.join(dir));
+            }
+            dirs.push(sysroot.join("lib").join(&multiarch));
+            dirs.push(sysroot.join("usr/lib").join(&multiarch));
+        }
+
+        let file_names = if statik {
+            vec![format!("lib{name}.a")]
+        } else {
+            vec![format!("lib{name}.so"), format!("lib{name}.dylib")]
+        };
+
+        let path = dirs
+            .iter()
+            .flat_map(|dir| file_names.iter().map(move |file_name| dir.join(file_name)))
+            .find(|path| path.exists())
+            .map(|path| Self::resolve_link(path, sysroot.as_deref()));
+
+        Self {
+            name: name.to_string(),
+            statik,
+            path,
+        }
+    }
+
+    /// Follow symbolic links, keeping absolute targets inside the sysroot.
+    fn resolve_link(mut path: PathBuf, sysroot: Option<&Path>) -> PathBuf {
+        for _ in 0..16 {
+            let Ok(target) = fs::read_link(&path) else {
+                break;
+            };
+
+            path = match (sysroot, target.strip_prefix("/")) {
+                (Some(sysroot), Ok(relative)) => sysroot.join(relative),
+                _ => path.parent().unwrap_or(Path::new("")).join(target),
+            };
+        }
+        path
+    }
+
+    /// Open the library to look up its symbols. Libraries for another target and static archives
+    /// cannot be loaded, so their symbol table is read instead.
+    fn symbols(&self) -> Result<Symbols, String> {
+        match &self.path {
+            Some(path) if self.statik || is_cross_compiling() => symbols::read(path)
+                .map(Symbols::Table)
+                .map_err(|err| format!("`{}`: {err}", path.display())),
+            Some(path) => unsafe { libloading::os::unix::Library::new(path) }
+                .map(Symbols::Loaded)
+                .map_err(|err| format!("`{}`: {err}", path.display())),
+            None if self.statik || is_cross_compiling() => Err(format!(
+                "could not find `lib{}` for the target. Set `{PAM_LIB_DIR_ENV_VAR}` or \
+                 `{SYSROOT_ENV_VAR}`",
+                self.name
+            )),
+            None => {
+                let file_name = format!("lib{}.so", self.name);
+                unsafe { libloading::os::unix::Library::new(&file_name) }
+                    .map(Symbols::Loaded)
+                    .map_err(|err| format!("`{file_name}`: {err}"))
+            }
+        }
+    }
+}
+
+enum Symbols {
+    Loaded(libloading::os::unix::Library),
+    Table(std::collections::HashSet<String>),
+}
+
+impl Symbols {
+    fn contains(&self, symbol: &str) -> bool {
+        match self {
+            Self::Loaded(library) => {
+                unsafe { library.get::<unsafe extern "C" fn()>(symbol.as_bytes()) }.is_ok()
+            }
+            Self::Table(symbols) => symbols.contains(symbol),
+        }
+    }
+}
+
+/// Symbols of Linux-PAM with the symbol version that introduced them, newest first
+const LINUX_PAM_VERSION_SYMBOLS: &[(&str, &str)] = &[
+    ("pam_modutil_check_user_in_passwd", "1.4.1"),
+    ("pam_start_confdir", "1.4.0"),
+    ("pam_modutil_search_key", "1.3.2"),
+    ("pam_modutil_sanitize_helper_fds", "1.1.9"),
+    ("pam_modutil_drop_priv", "1.1.3"),
+    ("pam_get_authtok_verify", "1.1.1"),
+    ("pam_get_authtok", "1.1.0"),
+    ("pam_start", "1.0.0"),
+];
+
+/// The cfgs that are emitted when Linux-PAM is at least the given release. Functions that were added
+/// in a release are gated with `#[since(...)]` in `src/lib.rs`.
+const LINUX_PAM_VERSION_CFGS: &[(&str, [u32; 3])] = &[
+    ("pam_linux_1_1", [1, 1, 0]),
+    ("pam_linux_1_1_1", [1, 1, 1]),
+    ("pam_linux_1_1_3", [1, 1, 3]),
+    ("pam_linux_1_1_9", [1, 1, 9]),
+    ("pam_linux_1_3_2", [1, 3, 2]),
+    ("pam_linux_1_4", [1, 4, 0]),
+    ("pam_linux_1_4_1", [1, 4, 1]),
+];
+
+/// Parse the numbers at the start of a version like `1.5.2`. Missing numbers are zero.
+///
+/// Unlike `PamVersion::parse`, which rejects anything but the numbers, a suffix like the `~rc1` of
+/// a distribution package is ignored, so `1.5.2~rc1` counts as `1.5.2`. The version only selects the
+/// cfgs, and a version that cannot be parsed at all emits every cfg, like an unknown version.
+fn parse_version(version: &str) -> Option<[u32; 3]> {
+    let end = version
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(version.len());
+
+    let mut parts = [0; 3];
+    for (part, number) in parts.iter_mut().zip(version[..end].split('.')) {
+        *part = number.parse().ok()?;
+    }
+    Some(parts)
+}
 
 #[derive(Debug, Clone, Copy)]
 enum PamImplementation {
@@ -10,8 +466,8 @@ enum PamImplementation {
 }
 
 impl PamImplementation {
-    fn resolve() -> Self {
-        if cfg!(feature = "linux-pam") {
+    fn resolve(library: &TargetLibrary) -> Self {
+        if cfg!(feature = "linux-pam") || cfg!(feature = "vendored") {
             Self::LinuxPAM
         } else if cfg!(feature = "openpam") {
             Self::OpenPAM
@@ -32,35 +488,24 @@ impl PamImplementation {
                 "cargo:warning=No explicit PAM implementation given. Attempting to detect implementation."
             );
 
-            let libpath = env::var("PAM_PATH").unwrap_or("libpam.so".to_string());
-
-            let implementation = {
-                match unsafe { libloading::os::unix::Library::new(&libpath) } {
-                    Ok(library) => {
-                        let is_openpam =
-                            unsafe { library.get::<unsafe extern "C" fn()>(b"openpam_ttyconv") }
-                                .is_ok();
-
-                        let implementation = if is_openpam {
-                            Self::OpenPAM
-                        } else {
-                            Self::LinuxPAM
-                        };
-
-                        println!(
-                            "cargo:warning=Detected implementation: `{}`.",
-                            implementation.display_str()
-                        );
-
-                        implementation
-                    }
-                    Err(err) => {
-                        println!(
-                            "cargo:warning=Failed to infer the PAM implementation in `{}`. Reason: `{}`",
-                            libpath, err
-                        );
+            let implementation = match library.symbols() {
+                Ok(symbols) => {
+                    let implementation = if symbols.contains("openpam_ttyconv") {
+                        Self::OpenPAM
+                    } else {
                         Self::LinuxPAM
-                    }
+                    };
+
+                    println!(
+                        "cargo:warning=Detected implementation: `{}`.",
+                        implementation.display_str()
+                    );
+
+                    implementation
+                }
+                Err(err) => {
+                    println!("cargo:warning=Failed to infer the PAM implementation. Reason: {err}");
+                    Self::LinuxPAM
                 }
             };
 
@@ -83,6 +528,31 @@ impl PamImplementation {
     fn set_feature(self) {
         let impl_name = self.impl_name();
         println!("cargo:rustc-cfg=pam_impl=\"{impl_name}\"");
+        // Readable by the build scripts of dependents as `DEP_PAM_IMPL`
+        println!("cargo:impl={impl_name}");
+    }
+
+    /// Determine the version of the library when the build did not find it.
+    ///
+    /// This asks pkg-config without linking, and otherwise looks for the newest symbol version
+    /// that Linux-PAM exports. OpenPAM has no such symbols.
+    fn probe_version(self, library: &TargetLibrary, use_pkg_config: bool) -> Option<String> {
+        if use_pkg_config {
+            let probe = pkg_config::Config::new().cargo_metadata(false).probe("pam");
+            if let Ok(library) = probe {
+                return Some(library.version);
+            }
+        }
+
+        let Self::LinuxPAM = self else {
+            return None;
+        };
+
+        let symbols = library.symbols().ok()?;
+        LINUX_PAM_VERSION_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| symbols.contains(symbol))
+            .map(|(_, version)| version.to_string())
     }
 
     fn display_str(self) -> &'static str {
@@ -94,17 +564,90 @@ impl PamImplementation {
 }
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(docsrs)");
+    println!("cargo:rustc-check-cfg=cfg(pam_impl, values(\"linux-pam\", \"openpam\"))");
+    for (cfg, _) in LINUX_PAM_VERSION_CFGS {
+        println!("cargo:rustc-check-cfg=cfg({cfg})");
+    }
+
     println!("cargo:rerun-if-env-changed={}", USE_LINUX_PAM_ENV_VAR);
     println!("cargo:rerun-if-env-changed={}", USE_OPENPAM_ENV_VAR);
-    println!("cargo:rerun-if-env-changed=PAM_PATH");
+    println!("cargo:rerun-if-env-changed={}", LEGACY_PAM_PATH_ENV_VAR);
+
+    let statik = target_env_var(PAM_STATIC_ENV_VAR).is_some_and(|v| v != "0");
+    let config = LinkConfig::from_env(statik);
+    let config_dirs: Vec<PathBuf> = config.iter().filter_map(|c| c.dir.clone()).collect();
+
+    #[cfg(feature = "vendored")]
+    let (version, lib_dirs) = (vendored::build(), config_dirs);
+
+    // With pkg-config, the directories it links from are searched. These include the sysroot.
+    #[cfg(not(feature = "vendored"))]
+    let (version, lib_dirs) = if cfg!(feature = "dlopen") {
+        // libpam is opened at runtime, so it does not have to be present when linking
+        (None, config_dirs)
+    } else if let Some(config) = &config {
+        config.link_search();
+        config.link_lib(&config.name);
+        (None, config_dirs)
+    } else {
+        let library = pkg_config::Config::new()
+            .statik(statik)
+            .probe("pam")
+            .expect("Failed to find libpam.so");
+        (Some(library.version), library.link_paths)
+    };
+
+    #[cfg(all(feature = "misc", not(feature = "vendored")))]
+    if cfg!(feature = "dlopen") {
+        // libpam_misc is opened at runtime as well
+    } else if let Some(config) = &config {
+        config.link_lib("pam_misc");
+    } else {
+        pkg_config::Config::new()
+            .statik(statik)
+            .probe("pam_misc")
+            .expect("Failed to find libpam_misc.so");
+    }
 
-    if let Ok(pam_path) = env::var("PAM_PATH") {
-        println!("cargo:rustc-link-lib={pam_path}");
+    #[cfg(all(feature = "pamc", not(feature = "vendored")))]
+    if cfg!(feature = "dlopen") {
+        // libpamc is opened at runtime as well
+    } else if let Some(config) = &config {
+        config.link_lib("pamc");
     } else {
-        pkg_config::probe_library("pam").expect("Failed to find libpam.so");
+        pkg_config::Config::new()
+            .statik(statik)
+            .probe("pamc")
+            .expect("Failed to find libpamc.so");
     }
 
+    let name = config.as_ref().map_or("pam", |config| &config.name);
+    let library = TargetLibrary::locate(name, statik, &lib_dirs);
+
     // Attempt to resolve with implementation is wanted by the user, and set it as the `pam-impl`
     // for the rustc cfg
-    PamImplementation::resolve().set_feature();
+    let implementation = PamImplementation::resolve(&library);
+    implementation.set_feature();
+
+    let version = version.or_else(|| implementation.probe_version(&library, config.is_none()));
+    if let Some(version) = &version {
+        println!("cargo:rustc-env=LIBPAM_SYS_PAM_VERSION={version}");
+        // Readable by the build scripts of dependents as `DEP_PAM_VERSION`
+        println!("cargo:version={version}");
+    }
+
+    if let PamImplementation::LinuxPAM = implementation {
+        // An unknown version is assumed to be recent, so every function stays linked
+        let version = version.as_deref().and_then(parse_version);
+        for (cfg, since) in LINUX_PAM_VERSION_CFGS {
+            let linked = match version {
+                Some(version) => version >= *since,
+                None => true,
+            };
+            if linked {
+                println!("cargo:rustc-cfg={cfg}");
+            }
+        }
+    }
 }
@@ -0,0 +1,49 @@
+//! How libpam is linked when it is given through the environment.
+//!
+//! This is included by the build script and by `tests/build_script.rs`.
+
+use std::path::{Path, PathBuf};
+
+/// How libpam is linked when it is given through the environment instead of pkg-config.
+pub struct LinkConfig {
+    pub dir: Option<PathBuf>,
+    pub name: String,
+    pub statik: bool,
+}
+
+impl LinkConfig {
+    /// Split a path like `/usr/lib/libpam.so` into its directory and library name. A plain name
+    /// like `pam` is used as the library name.
+    pub fn from_path(path: &Path) -> Self {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf);
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("pam");
+
+        let statik = file_name.ends_with(".a");
+        let name = file_name.strip_prefix("lib").unwrap_or(file_name);
+        let name = name.split_once(".so").map_or(name, |(name, _)| name);
+        let name = name.strip_suffix(".a").unwrap_or(name);
+
+        Self {
+            dir,
+            name: name.to_string(),
+            statik,
+        }
+    }
+
+    pub fn link_search(&self) {
+        if let Some(dir) = &self.dir {
+            println!("cargo:rustc-link-search=native={}", dir.display());
+        }
+    }
+
+    pub fn link_lib(&self, name: &str) {
+        let kind = if self.statik { "static" } else { "dylib" };
+        println!("cargo:rustc-link-lib={kind}={name}");
+    }
+}
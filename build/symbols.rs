@@ -0,0 +1,193 @@
+//! Reads the symbols of a library for the target without loading it, which is needed when
+//! cross-compiling.
+//!
+//! This is included by the build script and by `tests/build_script.rs`.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::{fs, io};
+
+/// The section type of the dynamic symbol table
+const SHT_DYNSYM: u32 = 11;
+/// The section index of undefined symbols
+const SHN_UNDEF: u16 = 0;
+
+/// Read the defined symbols of an ELF shared object, or the symbols of a static archive.
+pub fn read(path: &Path) -> io::Result<HashSet<String>> {
+    let data = fs::read(path)?;
+    let symbols = if data.starts_with(b"!<arch>\n") {
+        archive(&data)
+    } else {
+        elf(&data)
+    };
+
+    symbols.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an ELF shared object or a static archive with a symbol table",
+        )
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.data
+            .get(offset..offset.checked_add(N)?)?
+            .try_into()
+            .ok()
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, offset: usize) -> Option<u64> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+}
+
+struct Section {
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+    entsize: usize,
+}
+
+/// The names of the defined symbols in `.dynsym`
+fn elf(data: &[u8]) -> Option<HashSet<String>> {
+    if !data.starts_with(b"\x7fELF") {
+        return None;
+    }
+
+    let is_64 = match data.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let big_endian = match data.get(5)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let reader = Reader { data, big_endian };
+
+    let (shoff, shentsize, shnum) = if is_64 {
+        (
+            reader.u64(0x28)? as usize,
+            reader.u16(0x3a)?,
+            reader.u16(0x3c)?,
+        )
+    } else {
+        (
+            reader.u32(0x20)? as usize,
+            reader.u16(0x2e)?,
+            reader.u16(0x30)?,
+        )
+    };
+
+    let section = |index: usize| -> Option<Section> {
+        let base = shoff.checked_add(index.checked_mul(shentsize as usize)?)?;
+        Some(if is_64 {
+            Section {
+                kind: reader.u32(base + 4)?,
+                offset: reader.u64(base + 24)? as usize,
+                size: reader.u64(base + 32)? as usize,
+                link: reader.u32(base + 40)?,
+                entsize: reader.u64(base + 56)? as usize,
+            }
+        } else {
+            Section {
+                kind: reader.u32(base + 4)?,
+                offset: reader.u32(base + 16)? as usize,
+                size: reader.u32(base + 20)? as usize,
+                link: reader.u32(base + 24)?,
+                entsize: reader.u32(base + 36)? as usize,
+            }
+        })
+    };
+
+    let dynsym = (0..shnum as usize)
+        .filter_map(section)
+        .find(|section| section.kind == SHT_DYNSYM)?;
+    let strtab = section(dynsym.link as usize)?;
+    let strings = data.get(strtab.offset..strtab.offset.checked_add(strtab.size)?)?;
+
+    let entsize = match dynsym.entsize {
+        0 if is_64 => 24,
+        0 => 16,
+        entsize => entsize,
+    };
+
+    let mut symbols = HashSet::new();
+    // The first symbol is always the null symbol
+    for index in 1..dynsym.size / entsize {
+        let base = dynsym.offset.checked_add(index * entsize)?;
+        let name = reader.u32(base)? as usize;
+        let shndx = reader.u16(if is_64 { base + 6 } else { base + 14 })?;
+        if shndx == SHN_UNDEF {
+            continue;
+        }
+
+        let name = strings.get(name..)?;
+        let name = &name[..name.iter().position(|&byte| byte == 0)?];
+        if let Ok(name) = std::str::from_utf8(name) {
+            symbols.insert(name.to_string());
+        }
+    }
+
+    Some(symbols)
+}
+
+/// The names in the System V symbol table, which is the first member of the archive
+fn archive(data: &[u8]) -> Option<HashSet<String>> {
+    let header = data.get(8..68)?;
+    let name = std::str::from_utf8(&header[..16]).ok()?.trim_end();
+    let size = std::str::from_utf8(&header[48..58]).ok()?.trim_end();
+    let size: usize = size.parse().ok()?;
+    let member = data.get(68..68usize.checked_add(size)?)?;
+
+    let reader = Reader {
+        data: member,
+        big_endian: true,
+    };
+    let (count, width) = match name {
+        "/" => (reader.u32(0)? as usize, 4),
+        "/SYM64/" => (reader.u64(0)? as usize, 8),
+        _ => return None,
+    };
+
+    let strings = member.get(count.checked_add(1)?.checked_mul(width)?..)?;
+    Some(
+        strings
+            .split(|&byte| byte == 0)
+            .take(count)
+            .filter_map(|name| std::str::from_utf8(name).ok())
+            .map(String::from)
+            .collect(),
+    )
+}
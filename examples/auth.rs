@@ -1,93 +1,68 @@
-use core::slice;
-use std::ffi::{c_char, c_int, c_void, CStr};
-use std::io::{stdin, stdout, Write};
-
-use libpam_sys::{
-    pam_authenticate, pam_conv, pam_end, pam_handle_t, pam_message, pam_response, pam_start, pam_strerror, PAM_SUCCESS,
-};
-
+//! Authenticate with the `pam_example` service on the terminal.
+//!
+//! This uses the terminal conversation of the implementation, which is `misc_conv` of libpam_misc
+//! with Linux-PAM and `openpam_ttyconv` with OpenPAM. These are bound by the `misc` and `openpam`
+//! features.
+
+use std::ffi::{c_int, c_void};
+use std::process::ExitCode;
+use std::ptr;
+
+use libpam_sys::flags::AuthFlags;
+use libpam_sys::handle::PamHandle;
+#[cfg(feature = "misc")]
+use libpam_sys::linux_pam::misc_conv as terminal_conv;
+#[cfg(feature = "openpam")]
+use libpam_sys::openpam::openpam_ttyconv as terminal_conv;
+use libpam_sys::{pam_conv, pam_message, pam_response};
+
+/// Let libpam talk to the user on the terminal.
+///
+/// Both conversation functions allocate the responses with `malloc`, so libpam can free them, and
+/// do not echo the input for `PAM_PROMPT_ECHO_OFF` messages.
+#[cfg(any(feature = "misc", feature = "openpam"))]
 extern "C" fn conversation(
     num_msg: c_int,
-    msg: *const *mut pam_message,
+    msg: *mut *const pam_message,
     resp: *mut *mut pam_response,
-    _appdata_ptr: *mut c_void,
+    appdata_ptr: *mut c_void,
 ) -> c_int {
-    let num_msg = usize::try_from(num_msg).unwrap();
-    let msg = unsafe { slice::from_raw_parts(msg, num_msg) };
-    let mut responses = Vec::with_capacity(num_msg);
-
-    for i in 0..num_msg {
-        let mut line = String::new();
-
-        let msg_content = unsafe { msg[i].as_mut() }.unwrap().msg;
-        let msg_content = unsafe { CStr::from_ptr(msg_content) };
-
-        print!("{}", msg_content.to_str().unwrap());
-        stdout().flush().unwrap();
-
-        stdin().read_line(&mut line).unwrap();
-
-        line.pop();
-        line.push('\0');
-
-        let resp = line.as_ptr() as *mut c_char;
-        Box::leak(line.into_boxed_str());
-
-        let response = pam_response {
-            resp,
-            resp_retcode: 0,
-        };
-
-        responses.push(response);
-    }
-
-    unsafe { *resp = responses.leak().as_ptr() as *mut pam_response };
-
-    return PAM_SUCCESS;
+    unsafe { terminal_conv(num_msg, msg, resp, appdata_ptr) }
 }
 
-pub fn get_error(pamh: *mut pam_handle_t, errnum: c_int) -> &'static str {
-    let err_string = unsafe { pam_strerror(pamh, errnum) };
-    let err_string = unsafe { CStr::from_ptr(err_string) };
-    err_string.to_str().unwrap()
+/// Without a terminal conversation, every conversation fails.
+#[cfg(not(any(feature = "misc", feature = "openpam")))]
+extern "C" fn conversation(
+    _num_msg: c_int,
+    _msg: *mut *const pam_message,
+    _resp: *mut *mut pam_response,
+    _appdata_ptr: *mut c_void,
+) -> c_int {
+    eprintln!(
+        "Enable the `misc` feature with Linux-PAM or the `openpam` feature to answer prompts"
+    );
+    libpam_sys::PAM_CONV_ERR
 }
 
-fn main() {
-    let mut handler: pam_handle_t = unsafe { core::mem::zeroed() };
-    let mut pamh = &mut handler as *mut pam_handle_t;
-    
+fn main() -> ExitCode {
     let pam_conversation = pam_conv {
         conv: conversation,
-        app_dataptr: core::ptr::null::<c_void>() as *mut c_void,
+        app_dataptr: ptr::null_mut(),
     };
 
-    let mut service_name = String::from("pam_example");
-    service_name.push('\0');
-    let service_name = service_name.as_ptr() as *const c_char;
-    let user = core::ptr::null();
-
-    let retval = unsafe {
-        pam_start(
-            service_name,
-            user,
-            &pam_conversation as *const pam_conv,
-            &mut pamh as *mut *mut pam_handle_t,
-        )
+    let mut pamh = match PamHandle::start(c"pam_example", None, &pam_conversation) {
+        Ok(pamh) => pamh,
+        Err(err) => {
+            println!("{err}");
+            return ExitCode::FAILURE;
+        }
     };
 
-    if retval != PAM_SUCCESS {
-        println!("{}", get_error(pamh, retval));
+    // The handle passes the status of the last call to `pam_end` when it is dropped
+    if let Err(err) = pamh.authenticate(AuthFlags::empty()) {
+        println!("{err}");
+        return ExitCode::FAILURE;
     }
 
-    let retval = unsafe { pam_authenticate(pamh, 0) };
-
-    if retval != PAM_SUCCESS {
-        println!("{}", get_error(pamh, retval));
-    }
-
-    let retval = unsafe { pam_end(pamh, 0) };
-
-    if retval != PAM_SUCCESS {
-        println!("{}", get_error(pamh, retval));
-    }
+    ExitCode::SUCCESS
 }
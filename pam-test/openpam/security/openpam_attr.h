@@ -0,0 +1,65 @@
+/*-
+ * Copyright (c) 2002-2003 Networks Associates Technology, Inc.
+ * Copyright (c) 2004-2017 Dag-Erling Smørgrav
+ * All rights reserved.
+ *
+ * This software was developed for the FreeBSD Project by ThinkSec AS and
+ * Network Associates Laboratories, the Security Research Division of
+ * Network Associates, Inc.  under DARPA/SPAWAR contract N66001-01-C-8035
+ * ("CBOSS"), as part of the DARPA CHATS research program.
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions
+ * are met:
+ * 1. Redistributions of source code must retain the above copyright
+ *    notice, this list of conditions and the following disclaimer.
+ * 2. Redistributions in binary form must reproduce the above copyright
+ *    notice, this list of conditions and the following disclaimer in the
+ *    documentation and/or other materials provided with the distribution.
+ * 3. The name of the author may not be used to endorse or promote
+ *    products derived from this software without specific prior written
+ *    permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+ * ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+ * OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+ * HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+ * LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+ * OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+ * SUCH DAMAGE.
+ */
+
+#ifndef SECURITY_OPENPAM_ATTR_H_INCLUDED
+#define SECURITY_OPENPAM_ATTR_H_INCLUDED
+
+/* GCC attributes */
+#if defined(__GNUC__) && !defined(__LCLINT__)
+# define OPENPAM_GNUC_PREREQ(maj, min) \
+	((__GNUC__ * 100 + __GNUC_MINOR__) >= ((maj) * 100 + (min)))
+#else
+# define OPENPAM_GNUC_PREREQ(maj, min) 0
+#endif
+
+#if OPENPAM_GNUC_PREREQ(2,5)
+# define OPENPAM_FORMAT(params) __attribute__((__format__ params))
+#else
+# define OPENPAM_FORMAT(params)
+#endif
+
+#if OPENPAM_GNUC_PREREQ(3,3)
+# define OPENPAM_NONNULL(params) __attribute__((__nonnull__ params))
+#else
+# define OPENPAM_NONNULL(params)
+#endif
+
+#if OPENPAM_GNUC_PREREQ(2,5)
+# define OPENPAM_NORETURN __attribute__((__noreturn__))
+#else
+# define OPENPAM_NORETURN
+#endif
+
+#endif /* !SECURITY_OPENPAM_ATTR_H_INCLUDED */
@@ -0,0 +1,432 @@
+//! A small ABI verification harness for `libpam-sys`.
+//!
+//! The checks are collected from the Rust declarations and turned into a C program that includes
+//! the real PAM headers. That program is compiled and run when the tests run, and its output is
+//! compared with the Rust side. The C compiler is taken from `CC` and extra flags, such as include
+//! paths, from `CFLAGS`.
+//!
+//! Types are translated to C with the [`CType`] trait. Every pointer is written with `__typeof__`,
+//! so the C compiler can compare arbitrarily nested pointers and function pointers with
+//! `__builtin_types_compatible_p`.
+
+use std::env;
+use std::ffi::{c_char, c_int, c_long, c_uint, c_void};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use libpam_sys::{pam_conv, pam_handle_t, pam_message, pam_response, va_list};
+
+/// A Rust type that has a C equivalent.
+pub trait CType {
+    /// The spelling of the type in C
+    fn c_type() -> String;
+}
+
+macro_rules! c_types {
+    ($($ty:ty => $c:literal),* $(,)?) => {
+        $(
+            impl CType for $ty {
+                fn c_type() -> String {
+                    $c.to_string()
+                }
+            }
+        )*
+    };
+}
+
+c_types! {
+    () => "void",
+    c_void => "void",
+    c_char => "char",
+    c_int => "int",
+    c_uint => "unsigned int",
+    c_long => "long",
+    u8 => "uint8_t",
+    usize => "size_t",
+    libc::FILE => "FILE",
+    libc::passwd => "struct passwd",
+    libc::group => "struct group",
+    libc::spwd => "struct spwd",
+    pam_handle_t => "pam_handle_t",
+    pam_conv => "struct pam_conv",
+    pam_message => "struct pam_message",
+    pam_response => "struct pam_response",
+}
+
+// `va_list` is passed as a pointer in Rust. This is the type a `va_list` parameter has in C, where
+// the array type of x86_64 decays to a pointer to its element. Other targets pass a structure, which has no C type
+// to compare against, so functions taking a `va_list` are only checked on these targets.
+#[cfg(all(target_arch = "x86_64", not(windows)))]
+c_types! {
+    va_list => "__typeof__(&(*(va_list *)0)[0])",
+}
+
+#[cfg(any(
+    target_arch = "x86",
+    all(target_arch = "aarch64", target_vendor = "apple"),
+))]
+c_types! {
+    va_list => "char *",
+}
+
+/// Expand the checks of functions that take a `va_list`, on the targets where it has a C type.
+#[cfg(any(
+    all(target_arch = "x86_64", not(windows)),
+    target_arch = "x86",
+    all(target_arch = "aarch64", target_vendor = "apple"),
+))]
+#[macro_export]
+macro_rules! with_va_list {
+    ($($checks:tt)*) => {
+        $($checks)*
+    };
+}
+
+/// Expand the checks of functions that take a `va_list`, on the targets where it has a C type.
+#[cfg(not(any(
+    all(target_arch = "x86_64", not(windows)),
+    target_arch = "x86",
+    all(target_arch = "aarch64", target_vendor = "apple"),
+)))]
+#[macro_export]
+macro_rules! with_va_list {
+    ($($checks:tt)*) => {};
+}
+
+#[cfg(feature = "linux-pam")]
+c_types! {
+    libpam_sys::linux_pam::pam_modutil_privs => "struct pam_modutil_privs",
+    libpam_sys::linux_pam::pamc_binary_prompt => "__typeof__(*(pamc_bp_t)0)",
+    libpam_sys::linux_pam::pamc_handle_s => "struct pamc_handle_s",
+    libpam_sys::linux_pam::pam_xauth_data => "struct pam_xauth_data",
+}
+
+#[cfg(feature = "openpam")]
+c_types! {
+    libpam_sys::openpam::passwd => "struct passwd",
+    libpam_sys::openpam::pam_repository => "struct pam_repository",
+}
+
+impl<T: CType> CType for *const T {
+    fn c_type() -> String {
+        format!("__typeof__({}) const *", T::c_type())
+    }
+}
+
+impl<T: CType> CType for *mut T {
+    fn c_type() -> String {
+        format!("__typeof__({}) *", T::c_type())
+    }
+}
+
+/// Nullable function pointers
+impl<T: CType> CType for Option<T> {
+    fn c_type() -> String {
+        T::c_type()
+    }
+}
+
+macro_rules! c_fn_types {
+    ($($arg:ident),*) => {
+        c_fn_types!(@impl [extern "C" fn] $($arg),*);
+        c_fn_types!(@impl [unsafe extern "C" fn] $($arg),*);
+    };
+    (@impl [$($fn:tt)*] $($arg:ident),*) => {
+        impl<R: CType, $($arg: CType),*> CType for $($fn)*($($arg),*) -> R {
+            fn c_type() -> String {
+                let args: &[String] = &[$($arg::c_type()),*];
+                let args = if args.is_empty() { "void".to_string() } else { args.join(", ") };
+                format!("__typeof__({} (*)({}))", R::c_type(), args)
+            }
+        }
+    };
+}
+
+macro_rules! c_variadic_fn_types {
+    ($($arg:ident),+) => {
+        impl<R: CType, $($arg: CType),+> CType for unsafe extern "C" fn($($arg),+, ...) -> R {
+            fn c_type() -> String {
+                let args: &[String] = &[$($arg::c_type()),+];
+                format!("__typeof__({} (*)({}, ...))", R::c_type(), args.join(", "))
+            }
+        }
+    };
+}
+
+c_fn_types!();
+c_fn_types!(A);
+c_fn_types!(A, B);
+c_fn_types!(A, B, C);
+c_fn_types!(A, B, C, D);
+c_fn_types!(A, B, C, D, E);
+c_fn_types!(A, B, C, D, E, F);
+c_fn_types!(A, B, C, D, E, F, G);
+
+c_variadic_fn_types!(A);
+c_variadic_fn_types!(A, B);
+c_variadic_fn_types!(A, B, C);
+c_variadic_fn_types!(A, B, C, D);
+
+/// A value the C program prints and the Rust side expects.
+struct Check {
+    description: String,
+    c_expr: String,
+    expected: i64,
+}
+
+/// A collection of checks against a set of C headers.
+pub struct Abi {
+    headers: Vec<&'static str>,
+    include_dirs: Vec<PathBuf>,
+    checks: Vec<Check>,
+}
+
+impl Abi {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            include_dirs: Vec::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Search `dir` for headers before the system include directories.
+    pub fn include(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    fn check(&mut self, description: String, c_expr: String, expected: i64) -> &mut Self {
+        self.checks.push(Check {
+            description,
+            c_expr,
+            expected,
+        });
+        self
+    }
+
+    fn compatible(&mut self, description: String, c_expr: &str, c_type: String) -> &mut Self {
+        self.check(
+            description,
+            format!("__builtin_types_compatible_p(__typeof__({c_expr}), {c_type})"),
+            1,
+        )
+    }
+
+    /// Check the signature of the function `name`, which `function` returns.
+    ///
+    /// `function` is never called, so the function does not have to be linked. This allows checking
+    /// the bindings of an implementation that is not installed.
+    pub fn function<F: CType>(&mut self, name: &str, _function: impl FnOnce() -> F) -> &mut Self {
+        self.function_type::<F>(name)
+    }
+
+    /// Check that the function `name` has the function pointer type `F`.
+    pub fn function_type<F: CType>(&mut self, name: &str) -> &mut Self {
+        self.compatible(
+            format!("signature of `{name}`"),
+            &format!("&{name}"),
+            F::c_type(),
+        )
+    }
+
+    /// Check the type of the static variable `name`, whose address `address` returns.
+    ///
+    /// Like with [`Abi::function`], `address` is never called.
+    pub fn static_item<T: CType>(
+        &mut self,
+        name: &str,
+        _address: impl FnOnce() -> *const T,
+    ) -> &mut Self {
+        self.compatible(format!("type of `{name}`"), name, T::c_type())
+    }
+
+    /// Check the value of the constant `name`.
+    pub fn constant(&mut self, name: &str, value: impl Into<i64>) -> &mut Self {
+        self.check(format!("value of `{name}`"), name.to_string(), value.into())
+    }
+
+    /// Check the size and alignment of `c_struct`.
+    pub fn layout<S>(&mut self, c_struct: &str) -> &mut Self {
+        self.check(
+            format!("size of `{c_struct}`"),
+            format!("sizeof({c_struct})"),
+            std::mem::size_of::<S>() as i64,
+        )
+        .check(
+            format!("alignment of `{c_struct}`"),
+            format!("_Alignof({c_struct})"),
+            std::mem::align_of::<S>() as i64,
+        )
+    }
+
+    /// Check the offset and type of `field` in `c_struct`.
+    pub fn field<S, F: CType>(
+        &mut self,
+        c_struct: &str,
+        field: &str,
+        offset: usize,
+        _: fn(&S) -> &F,
+    ) -> &mut Self {
+        self.check(
+            format!("offset of `{c_struct}.{field}`"),
+            format!("offsetof({c_struct}, {field})"),
+            offset as i64,
+        )
+        .compatible(
+            format!("type of `{c_struct}.{field}`"),
+            &format!("(({c_struct} *)0)->{field}"),
+            F::c_type(),
+        )
+    }
+
+    /// The C program that prints the value of every check on a separate line.
+    pub fn c_source(&self) -> String {
+        let mut source = String::from(
+            "#include <stdarg.h>\n#include <stddef.h>\n#include <stdint.h>\n#include <stdio.h>\n\n",
+        );
+
+        for header in &self.headers {
+            writeln!(source, "#include <{header}>").unwrap();
+        }
+
+        source.push_str("\nint main(void) {\n");
+        for check in &self.checks {
+            writeln!(
+                source,
+                "    printf(\"%lld\\n\", (long long)({}));",
+                check.c_expr
+            )
+            .unwrap();
+        }
+        source.push_str("    return 0;\n}\n");
+
+        source
+    }
+
+    /// Compile and run the C program, and panic with every mismatch that was found.
+    pub fn run(&self, name: &str) {
+        let dir = env::var_os("PAM_TEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(env::temp_dir)
+            .join(format!("pam-test-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let source = dir.join(format!("{name}.c"));
+        let binary = dir.join(name);
+        fs::write(&source, self.c_source()).unwrap();
+
+        let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let cflags = env::var("CFLAGS").unwrap_or_default();
+
+        let output = Command::new(&cc)
+            .args(
+                self.include_dirs
+                    .iter()
+                    .map(|dir| format!("-I{}", dir.display())),
+            )
+            .args(cflags.split_whitespace())
+            .arg("-o")
+            .arg(&binary)
+            .arg(&source)
+            .output()
+            .unwrap_or_else(|err| panic!("Failed to run `{cc}`: {err}"));
+        if !output.status.success() {
+            panic!(
+                "Failed to compile `{}`:\n{}",
+                source.display(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let output = Command::new(&binary).output().unwrap();
+        assert!(output.status.success(), "`{}` failed", binary.display());
+        let output = String::from_utf8(output.stdout).unwrap();
+
+        let mut mismatches = Vec::new();
+        for (check, line) in self.checks.iter().zip(output.lines()) {
+            let actual: i64 = line.parse().unwrap();
+            if actual == check.expected {
+                continue;
+            }
+
+            if check.c_expr.starts_with("__builtin_types_compatible_p") {
+                mismatches.push(format!(
+                    "{} differs between Rust and C (`{}`)",
+                    check.description, check.c_expr
+                ));
+            } else {
+                mismatches.push(format!(
+                    "{}: Rust has {} and C has {}",
+                    check.description, check.expected, actual
+                ));
+            }
+        }
+
+        assert_eq!(output.lines().count(), self.checks.len());
+        assert!(
+            mismatches.is_empty(),
+            "{} ABI mismatches:\n{}",
+            mismatches.len(),
+            mismatches.join("\n")
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
+
+/// Check the signatures of functions in a module, with `_` for every argument.
+#[macro_export]
+macro_rules! functions {
+    ($abi:expr; $module:path { $($name:ident($($arg:tt),*)),* $(,)? }) => {{
+        use $module as module;
+        $(
+            $abi.function(
+                stringify!($name),
+                || module::$name as unsafe extern "C" fn($($arg),*) -> _,
+            );
+        )*
+    }};
+}
+
+/// Check the layout and field types of structs.
+///
+/// A field that is named differently in C is written as `rust_name = "c_name"`.
+#[macro_export]
+macro_rules! structs {
+    (
+        $abi:expr;
+        $($c_struct:literal => $ty:ty { $($field:ident $(= $c_field:literal)?),* $(,)? }),* $(,)?
+    ) => {
+        $(
+            $abi.layout::<$ty>($c_struct);
+            $(
+                $abi.field(
+                    $c_struct,
+                    $crate::structs!(@c_field $field $($c_field)?),
+                    std::mem::offset_of!($ty, $field),
+                    |s: &$ty| &s.$field,
+                );
+            )*
+        )*
+    };
+    (@c_field $field:ident) => {
+        stringify!($field)
+    };
+    (@c_field $field:ident $c_field:literal) => {
+        $c_field
+    };
+}
+
+/// Check the values of constants.
+#[macro_export]
+macro_rules! constants {
+    ($abi:expr; $module:path { $($name:ident),* $(,)? }) => {{
+        use $module as module;
+        $(
+            $abi.constant(stringify!($name), module::$name);
+        )*
+    }};
+}
@@ -0,0 +1,282 @@
+//! Verifies every declared function, struct and constant against the installed PAM headers.
+
+use libpam_sys::module::pam_sm_fn;
+use libpam_sys::{pam_conv, pam_message, pam_response};
+use pam_test::{constants, functions, structs, Abi};
+
+#[cfg(feature = "linux-pam")]
+const HEADERS: &[&str] = &[
+    "security/pam_appl.h",
+    "security/pam_modules.h",
+    "security/pam_ext.h",
+    "security/pam_modutil.h",
+    "security/pam_misc.h",
+    "security/pam_client.h",
+];
+
+#[cfg(feature = "openpam")]
+const HEADERS: &[&str] = &[
+    "security/pam_appl.h",
+    "security/pam_modules.h",
+    "security/openpam.h",
+];
+
+#[test]
+fn abi() {
+    let mut abi = Abi::new(HEADERS);
+    #[cfg(feature = "openpam")]
+    abi.include(concat!(env!("CARGO_MANIFEST_DIR"), "/openpam"));
+
+    functions!(abi; libpam_sys {
+        // Application functions
+        pam_start(_, _, _, _),
+        pam_end(_, _),
+        pam_authenticate(_, _),
+        pam_setcred(_, _),
+        pam_acct_mgmt(_, _),
+        pam_open_session(_, _),
+        pam_close_session(_, _),
+        pam_chauthtok(_, _),
+
+        // General Functions
+        pam_strerror(_, _),
+        pam_set_item(_, _, _),
+        pam_get_item(_, _, _),
+        pam_getenv(_, _),
+        pam_putenv(_, _),
+        pam_getenvlist(_),
+        pam_get_user(_, _, _),
+        pam_get_data(_, _, _),
+        pam_set_data(_, _, _, _),
+    });
+
+    // Module functions
+    for name in [
+        "pam_sm_authenticate",
+        "pam_sm_setcred",
+        "pam_sm_acct_mgmt",
+        "pam_sm_open_session",
+        "pam_sm_close_session",
+        "pam_sm_chauthtok",
+    ] {
+        abi.function_type::<pam_sm_fn>(name);
+    }
+
+    #[cfg(feature = "linux-pam")]
+    functions!(abi; libpam_sys::linux_pam {
+        pam_start_confdir(_, _, _, _, _),
+        pam_fail_delay(_, _),
+        pam_syslog(_, _, _, ...),
+        pam_prompt(_, _, _, _, ...),
+        pam_get_authtok(_, _, _, _),
+        pam_get_authtok_noverify(_, _, _),
+        pam_get_authtok_verify(_, _, _),
+        pam_modutil_check_user_in_passwd(_, _, _),
+        pam_modutil_getpwnam(_, _),
+        pam_modutil_getpwuid(_, _),
+        pam_modutil_getgrnam(_, _),
+        pam_modutil_getgrgid(_, _),
+        pam_modutil_getspnam(_, _),
+        pam_modutil_user_in_group_nam_nam(_, _, _),
+        pam_modutil_user_in_group_nam_gid(_, _, _),
+        pam_modutil_user_in_group_uid_nam(_, _, _),
+        pam_modutil_user_in_group_uid_gid(_, _, _),
+        pam_modutil_getlogin(_),
+        pam_modutil_read(_, _, _),
+        pam_modutil_write(_, _, _),
+        pam_modutil_audit_write(_, _, _, _),
+        pam_modutil_drop_priv(_, _, _),
+        pam_modutil_regain_priv(_, _),
+        pam_modutil_sanitize_helper_fds(_, _, _, _),
+        pam_modutil_search_key(_, _, _),
+    });
+
+    #[cfg(feature = "linux-pam")]
+    pam_test::with_va_list! {
+        functions!(abi; libpam_sys::linux_pam {
+            pam_vsyslog(_, _, _, _),
+            pam_vprompt(_, _, _, _, _),
+        });
+    }
+
+    #[cfg(feature = "linux-pam")]
+    {
+        structs!(abi;
+            "struct pam_modutil_privs" => libpam_sys::linux_pam::pam_modutil_privs {
+                grplist,
+                number_of_groups,
+                allocated,
+                old_gid,
+                old_uid,
+                is_dropped,
+            },
+            "struct pam_xauth_data" => libpam_sys::linux_pam::pam_xauth_data {
+                namelen,
+                name,
+                datalen,
+                data,
+            },
+        );
+
+        functions!(abi; libpam_sys::linux_pam {
+            misc_conv(_, _, _, _),
+            pam_misc_paste_env(_, _),
+            pam_misc_drop_env(_),
+            pam_misc_setenv(_, _, _, _),
+            pamc_start(),
+            pamc_end(_),
+            pamc_load(_, _),
+            pamc_disable(_, _),
+            pamc_converse(_, _),
+            pamc_list_agents(_),
+        });
+
+        use libpam_sys::linux_pam as misc;
+        abi.static_item("pam_misc_conv_warn_time", || {
+            &raw const misc::pam_misc_conv_warn_time
+        })
+        .static_item("pam_misc_conv_die_time", || {
+            &raw const misc::pam_misc_conv_die_time
+        })
+        .static_item("pam_misc_conv_died", || &raw const misc::pam_misc_conv_died)
+        .static_item("pam_misc_conv_warn_line", || {
+            &raw const misc::pam_misc_conv_warn_line
+        })
+        .static_item("pam_misc_conv_die_line", || {
+            &raw const misc::pam_misc_conv_die_line
+        })
+        .static_item("pam_binary_handler_fn", || {
+            &raw const misc::pam_binary_handler_fn
+        })
+        .static_item("pam_binary_handler_free", || {
+            &raw const misc::pam_binary_handler_free
+        });
+
+        structs!(abi;
+            "__typeof__(*(pamc_bp_t)0)" => libpam_sys::linux_pam::pamc_binary_prompt {
+                length,
+                control,
+            },
+        );
+
+        constants!(abi; libpam_sys::linux_pam {
+            PAM_MODUTIL_NGROUPS,
+            PAM_MODUTIL_IGNORE_FD,
+            PAM_MODUTIL_PIPE_FD,
+            PAM_MODUTIL_NULL_FD,
+        });
+    }
+
+    #[cfg(feature = "openpam")]
+    {
+        functions!(abi; libpam_sys::openpam {
+            openpam_borrow_cred(_, _),
+            openpam_subst(_, _, _, _),
+            openpam_free_data(_, _, _),
+            openpam_free_envlist(_),
+            openpam_get_option(_, _),
+            openpam_restore_cred(_),
+            openpam_set_option(_, _, _),
+            pam_error(_, _, ...),
+            pam_get_authtok(_, _, _, _),
+            pam_info(_, _, ...),
+            pam_prompt(_, _, _, _, ...),
+            pam_setenv(_, _, _, _),
+            openpam_straddch(_, _, _, _),
+            openpam_set_feature(_, _),
+            openpam_get_feature(_, _),
+            _openpam_log(_, _, _, ...),
+            openpam_ttyconv(_, _, _, _),
+            openpam_nullconv(_, _, _, _),
+            openpam_readline(_, _, _),
+            openpam_readlinev(_, _, _),
+            openpam_readword(_, _, _),
+        });
+
+        pam_test::with_va_list! {
+            functions!(abi; libpam_sys::openpam {
+                pam_verror(_, _, _),
+                pam_vinfo(_, _, _),
+                pam_vprompt(_, _, _, _, _),
+            });
+        }
+
+        abi.static_item("openpam_ttyconv_timeout", || {
+            &raw const libpam_sys::openpam::openpam_ttyconv_timeout
+        });
+
+        structs!(abi;
+            "struct pam_repository" => libpam_sys::openpam::pam_repository {
+                type_ = "type",
+                scope,
+                scope_len,
+            },
+        );
+    }
+
+    structs!(abi;
+        "struct pam_conv" => pam_conv { conv, app_dataptr = "appdata_ptr" },
+        "struct pam_message" => pam_message { msg_style, msg },
+        "struct pam_response" => pam_response { resp, resp_retcode },
+    );
+
+    constants!(abi; libpam_sys {
+        PAM_SUCCESS,
+        PAM_OPEN_ERR,
+        PAM_SYMBOL_ERR,
+        PAM_SERVICE_ERR,
+        PAM_SYSTEM_ERR,
+        PAM_BUF_ERR,
+        PAM_PERM_DENIED,
+        PAM_AUTH_ERR,
+        PAM_CRED_INSUFFICIENT,
+        PAM_AUTHINFO_UNAVAIL,
+        PAM_USER_UNKNOWN,
+        PAM_MAXTRIES,
+        PAM_NEW_AUTHTOK_REQD,
+        PAM_ACCT_EXPIRED,
+        PAM_SESSION_ERR,
+        PAM_CRED_UNAVAIL,
+        PAM_CRED_EXPIRED,
+        PAM_CRED_ERR,
+        PAM_NO_MODULE_DATA,
+        PAM_CONV_ERR,
+        PAM_AUTHTOK_ERR,
+        PAM_AUTHTOK_RECOVERY_ERR,
+        PAM_AUTHTOK_LOCK_BUSY,
+        PAM_AUTHTOK_DISABLE_AGING,
+        PAM_TRY_AGAIN,
+        PAM_IGNORE,
+        PAM_ABORT,
+        PAM_AUTHTOK_EXPIRED,
+        PAM_MODULE_UNKNOWN,
+        PAM_BAD_ITEM,
+        PAM_PROMPT_ECHO_OFF,
+        PAM_PROMPT_ECHO_ON,
+        PAM_ERROR_MSG,
+        PAM_TEXT_INFO,
+        PAM_MAX_NUM_MSG,
+        PAM_MAX_MSG_SIZE,
+        PAM_MAX_RESP_SIZE,
+        PAM_SILENT,
+        PAM_DISALLOW_NULL_AUTHTOK,
+        PAM_ESTABLISH_CRED,
+        PAM_DELETE_CRED,
+        PAM_REINITIALIZE_CRED,
+        PAM_REFRESH_CRED,
+        PAM_PRELIM_CHECK,
+        PAM_UPDATE_AUTHTOK,
+        PAM_CHANGE_EXPIRED_AUTHTOK,
+        PAM_SERVICE,
+        PAM_USER,
+        PAM_TTY,
+        PAM_RHOST,
+        PAM_CONV,
+        PAM_AUTHTOK,
+        PAM_OLDAUTHTOK,
+        PAM_RUSER,
+        PAM_USER_PROMPT,
+    });
+
+    abi.run("abi");
+}
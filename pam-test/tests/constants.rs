@@ -0,0 +1,206 @@
+//! Verifies the constant tables of the `linux_pam` and `openpam` modules.
+//!
+//! Linux-PAM is checked against the headers installed on the system. OpenPAM is checked against
+//! the copy of its headers in `openpam/`.
+
+use pam_test::{constants, Abi};
+
+#[cfg(feature = "linux-pam")]
+#[test]
+fn linux_pam() {
+    let mut abi = Abi::new(&[
+        "security/_pam_types.h",
+        "security/pam_modules.h",
+        "security/pam_client.h",
+    ]);
+
+    constants!(abi; libpam_sys::linux_pam {
+        // XSSO 5.2 PAM Status Codes
+        PAM_SUCCESS,
+        PAM_OPEN_ERR,
+        PAM_SYMBOL_ERR,
+        PAM_SERVICE_ERR,
+        PAM_SYSTEM_ERR,
+        PAM_BUF_ERR,
+        PAM_PERM_DENIED,
+        PAM_AUTH_ERR,
+        PAM_CRED_INSUFFICIENT,
+        PAM_AUTHINFO_UNAVAIL,
+        PAM_USER_UNKNOWN,
+        PAM_MAXTRIES,
+        PAM_NEW_AUTHTOK_REQD,
+        PAM_ACCT_EXPIRED,
+        PAM_SESSION_ERR,
+        PAM_CRED_UNAVAIL,
+        PAM_CRED_EXPIRED,
+        PAM_CRED_ERR,
+        PAM_NO_MODULE_DATA,
+        PAM_CONV_ERR,
+        PAM_AUTHTOK_ERR,
+        PAM_AUTHTOK_RECOVERY_ERR,
+        PAM_AUTHTOK_LOCK_BUSY,
+        PAM_AUTHTOK_DISABLE_AGING,
+        PAM_TRY_AGAIN,
+        PAM_IGNORE,
+        PAM_ABORT,
+        PAM_AUTHTOK_EXPIRED,
+        PAM_MODULE_UNKNOWN,
+        PAM_BAD_ITEM,
+        PAM_CONV_AGAIN,
+        PAM_INCOMPLETE,
+        _PAM_RETURN_VALUES,
+
+        // XSSO 5.3 Constants
+        PAM_PROMPT_ECHO_OFF,
+        PAM_PROMPT_ECHO_ON,
+        PAM_ERROR_MSG,
+        PAM_TEXT_INFO,
+        PAM_MAX_NUM_MSG,
+        PAM_MAX_MSG_SIZE,
+        PAM_MAX_RESP_SIZE,
+        PAM_RADIO_TYPE,
+        PAM_BINARY_PROMPT,
+        PAM_BPC_FALSE,
+        PAM_BPC_TRUE,
+        PAM_BPC_OK,
+        PAM_BPC_SELECT,
+        PAM_BPC_DONE,
+        PAM_BPC_FAIL,
+        PAM_BPC_GETENV,
+        PAM_BPC_PUTENV,
+        PAM_BPC_TEXT,
+        PAM_BPC_ERROR,
+        PAM_BPC_PROMPT,
+        PAM_BPC_PASS,
+        PAM_BP_MIN_SIZE,
+        PAM_BP_MAX_LENGTH,
+
+        // XSSO 5.4 Flags
+        PAM_SILENT,
+        PAM_DISALLOW_NULL_AUTHTOK,
+        PAM_ESTABLISH_CRED,
+        PAM_DELETE_CRED,
+        PAM_REINITIALIZE_CRED,
+        PAM_REFRESH_CRED,
+        PAM_CHANGE_EXPIRED_AUTHTOK,
+        PAM_PRELIM_CHECK,
+        PAM_UPDATE_AUTHTOK,
+        PAM_DATA_REPLACE,
+        PAM_DATA_SILENT,
+
+        // XSSO 5.5 Item_type
+        PAM_SERVICE,
+        PAM_USER,
+        PAM_TTY,
+        PAM_RHOST,
+        PAM_CONV,
+        PAM_AUTHTOK,
+        PAM_OLDAUTHTOK,
+        PAM_RUSER,
+        PAM_USER_PROMPT,
+        PAM_FAIL_DELAY,
+        PAM_XDISPLAY,
+        PAM_XAUTHDATA,
+        PAM_AUTHTOK_TYPE,
+    });
+
+    abi.run("linux_pam_constants");
+}
+
+#[test]
+fn openpam() {
+    let mut abi = Abi::new(&["security/pam_constants.h", "security/openpam.h"]);
+    abi.include(concat!(env!("CARGO_MANIFEST_DIR"), "/openpam"));
+
+    constants!(abi; libpam_sys::openpam {
+        // XSSO 5.2 PAM Status Codes
+        PAM_SUCCESS,
+        PAM_OPEN_ERR,
+        PAM_SYMBOL_ERR,
+        PAM_SERVICE_ERR,
+        PAM_SYSTEM_ERR,
+        PAM_BUF_ERR,
+        PAM_CONV_ERR,
+        PAM_PERM_DENIED,
+        PAM_MAXTRIES,
+        PAM_AUTH_ERR,
+        PAM_NEW_AUTHTOK_REQD,
+        PAM_CRED_INSUFFICIENT,
+        PAM_AUTHINFO_UNAVAIL,
+        PAM_USER_UNKNOWN,
+        PAM_CRED_UNAVAIL,
+        PAM_CRED_EXPIRED,
+        PAM_CRED_ERR,
+        PAM_ACCT_EXPIRED,
+        PAM_AUTHTOK_EXPIRED,
+        PAM_SESSION_ERR,
+        PAM_AUTHTOK_ERR,
+        PAM_AUTHTOK_RECOVERY_ERR,
+        PAM_AUTHTOK_LOCK_BUSY,
+        PAM_AUTHTOK_DISABLE_AGING,
+        PAM_NO_MODULE_DATA,
+        PAM_IGNORE,
+        PAM_ABORT,
+        PAM_TRY_AGAIN,
+        PAM_MODULE_UNKNOWN,
+        PAM_DOMAIN_UNKNOWN,
+        PAM_BAD_HANDLE,
+        PAM_BAD_ITEM,
+        PAM_BAD_FEATURE,
+        PAM_BAD_CONSTANT,
+        PAM_NUM_ERRORS,
+
+        // XSSO 5.3 Constants
+        PAM_PROMPT_ECHO_OFF,
+        PAM_PROMPT_ECHO_ON,
+        PAM_ERROR_MSG,
+        PAM_TEXT_INFO,
+        PAM_MAX_NUM_MSG,
+        PAM_MAX_MSG_SIZE,
+        PAM_MAX_RESP_SIZE,
+
+        // XSSO 5.4 Flags
+        PAM_SILENT,
+        PAM_DISALLOW_NULL_AUTHTOK,
+        PAM_ESTABLISH_CRED,
+        PAM_DELETE_CRED,
+        PAM_REINITIALIZE_CRED,
+        PAM_REFRESH_CRED,
+        PAM_PRELIM_CHECK,
+        PAM_UPDATE_AUTHTOK,
+        PAM_CHANGE_EXPIRED_AUTHTOK,
+
+        // XSSO 5.5 Item_type
+        PAM_SERVICE,
+        PAM_USER,
+        PAM_TTY,
+        PAM_RHOST,
+        PAM_CONV,
+        PAM_AUTHTOK,
+        PAM_OLDAUTHTOK,
+        PAM_RUSER,
+        PAM_USER_PROMPT,
+        PAM_REPOSITORY,
+        PAM_AUTHTOK_PROMPT,
+        PAM_OLDAUTHTOK_PROMPT,
+        PAM_HOST,
+        PAM_NUM_ITEMS,
+    });
+
+    #[cfg(feature = "openpam")]
+    constants!(abi; libpam_sys::openpam {
+        OPENPAM_RESTRICT_SERVICE_NAME,
+        OPENPAM_VERIFY_POLICY_FILE,
+        OPENPAM_RESTRICT_MODULE_NAME,
+        OPENPAM_VERIFY_MODULE_FILE,
+        OPENPAM_FALLBACK_TO_OTHER,
+        OPENPAM_NUM_FEATURES,
+        PAM_LOG_LIBDEBUG,
+        PAM_LOG_DEBUG,
+        PAM_LOG_VERBOSE,
+        PAM_LOG_NOTICE,
+        PAM_LOG_ERROR,
+    });
+
+    abi.run("openpam_constants");
+}
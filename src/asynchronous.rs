@@ -0,0 +1,403 @@
+//! An async API for PAM transactions.
+//!
+//! The functions of libpam block, and so does the conversation while it waits for the user.
+//! [`AsyncPamHandle`] runs the transaction on a dedicated thread instead, and its methods wait for
+//! the calls without blocking. The messages of the conversation arrive as the [`Prompts`] stream,
+//! and a [`Prompt`] is answered from any task.
+//!
+//! The channels do not depend on a runtime, so this works with tokio as well as with any other
+//! executor. The prompts have to be handled while a call is waiting, for example in another task.
+//!
+//! ```no_run
+//! use std::future::poll_fn;
+//! use std::pin::Pin;
+//! use std::thread;
+//!
+//! use futures_core::Stream;
+//! use futures_executor::block_on;
+//! use libpam_sys::asynchronous::AsyncPamHandle;
+//! use libpam_sys::flags::AuthFlags;
+//!
+//! let (mut pamh, mut prompts) = block_on(AsyncPamHandle::start(c"login", Some(c"user")))?;
+//!
+//! // Any other task or thread can answer the prompts while the calls are waited for
+//! let conversation = thread::spawn(move || {
+//!     block_on(async move {
+//!         while let Some(prompt) = poll_fn(|cx| Pin::new(&mut prompts).poll_next(cx)).await {
+//!             println!("{}", prompt.text().to_string_lossy());
+//!             if prompt.needs_answer() {
+//!                 prompt.answer(c"hunter2".to_owned());
+//!             }
+//!         }
+//!     })
+//! });
+//!
+//! block_on(async move {
+//!     pamh.authenticate(AuthFlags::empty()).await?;
+//!     pamh.acct_mgmt(AuthFlags::empty()).await?;
+//!     pamh.end().await
+//! })?;
+//! conversation.join().unwrap();
+//! # Ok::<(), libpam_sys::error::PamError>(())
+//! ```
+
+use std::ffi::{c_int, CStr, CString};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::{Context, Poll};
+use std::thread;
+
+use futures_channel::{mpsc as stream, oneshot};
+use futures_core::Stream;
+
+use crate::conv::{self, Conversation};
+use crate::error::PamError;
+use crate::flags::{AuthFlags, ChauthtokFlags, CredFlags, SessionFlags};
+use crate::handle::PamHandle;
+use crate::{PAM_ERROR_MSG, PAM_PROMPT_ECHO_OFF, PAM_PROMPT_ECHO_ON, PAM_TEXT_INFO};
+
+/// A message of the conversation.
+pub struct Prompt {
+    style: c_int,
+    text: CString,
+    /// Where the answer is sent, for messages that need one
+    reply: Option<mpsc::SyncSender<CString>>,
+}
+
+impl Prompt {
+    /// The `msg_style` of the message
+    pub fn style(&self) -> c_int {
+        self.style
+    }
+
+    /// The text of the message
+    pub fn text(&self) -> &CStr {
+        &self.text
+    }
+
+    /// Returns whether the conversation waits for an answer.
+    ///
+    /// Dropping a prompt that needs an answer fails the conversation with `PAM_CONV_ERR`.
+    pub fn needs_answer(&self) -> bool {
+        self.reply.is_some()
+    }
+
+    /// Answer the prompt. The answer is ignored for messages that do not need one.
+    pub fn answer(mut self, answer: CString) {
+        if let Some(reply) = self.reply.take() {
+            // The conversation only stops waiting when the transaction thread has ended
+            let _ = reply.send(answer);
+        }
+    }
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("style", &self.style)
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The stream of the messages of the conversation of an [`AsyncPamHandle`].
+///
+/// The stream ends when the transaction has ended.
+#[derive(Debug)]
+pub struct Prompts(stream::UnboundedReceiver<Prompt>);
+
+impl Stream for Prompts {
+    type Item = Prompt;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Prompt>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// The conversation on the transaction thread, which forwards the messages to [`Prompts`].
+struct Channel(stream::UnboundedSender<Prompt>);
+
+impl Channel {
+    fn ask(&mut self, style: c_int, msg: &CStr) -> conv::Result<CString> {
+        let (reply, answer) = mpsc::sync_channel(1);
+        self.send(style, msg, Some(reply))?;
+        Ok(answer.recv().map_err(|_| "The prompt was not answered")?)
+    }
+
+    fn send(
+        &mut self,
+        style: c_int,
+        msg: &CStr,
+        reply: Option<mpsc::SyncSender<CString>>,
+    ) -> conv::Result<()> {
+        let prompt = Prompt {
+            style,
+            text: msg.to_owned(),
+            reply,
+        };
+
+        Ok(self
+            .0
+            .unbounded_send(prompt)
+            .map_err(|_| "The prompts are not received")?)
+    }
+}
+
+impl Conversation for Channel {
+    fn prompt_echo_on(&mut self, msg: &CStr) -> conv::Result<CString> {
+        self.ask(PAM_PROMPT_ECHO_ON, msg)
+    }
+
+    fn prompt_echo_off(&mut self, msg: &CStr) -> conv::Result<CString> {
+        self.ask(PAM_PROMPT_ECHO_OFF, msg)
+    }
+
+    fn error_msg(&mut self, msg: &CStr) -> conv::Result<()> {
+        self.send(PAM_ERROR_MSG, msg, None)
+    }
+
+    fn text_info(&mut self, msg: &CStr) -> conv::Result<()> {
+        self.send(PAM_TEXT_INFO, msg, None)
+    }
+
+    #[cfg(feature = "linux-pam")]
+    fn radio_type(&mut self, msg: &CStr) -> conv::Result<CString> {
+        self.ask(crate::linux_pam::PAM_RADIO_TYPE, msg)
+    }
+}
+
+/// A call that is made on the transaction thread, with its flags.
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Authenticate(AuthFlags),
+    AcctMgmt(AuthFlags),
+    Setcred(CredFlags),
+    OpenSession(SessionFlags),
+    CloseSession(SessionFlags),
+    Chauthtok(ChauthtokFlags),
+    End,
+}
+
+type Request = (Operation, oneshot::Sender<Result<(), PamError>>);
+
+/// A PAM transaction that runs on a dedicated thread.
+///
+/// Dropping the handle ends the transaction once the current call has returned.
+#[derive(Debug)]
+pub struct AsyncPamHandle {
+    requests: mpsc::Sender<Request>,
+}
+
+impl AsyncPamHandle {
+    /// Start a transaction for `service` on a new thread, with a conversation that sends its
+    /// messages to the returned [`Prompts`].
+    pub async fn start(service: &CStr, user: Option<&CStr>) -> Result<(Self, Prompts), PamError> {
+        let service = service.to_owned();
+        let user = user.map(CStr::to_owned);
+
+        let (prompts, prompts_receiver) = stream::unbounded();
+        let (requests, requests_receiver) = mpsc::channel();
+        let (started, started_receiver) = oneshot::channel();
+
+        thread::Builder::new()
+            .name("pam".to_string())
+            .spawn(move || {
+                match PamHandle::start_with(&service, user.as_deref(), Channel(prompts)) {
+                    Ok(pamh) => {
+                        let _ = started.send(Ok(()));
+                        run(pamh, requests_receiver);
+                    }
+                    Err(err) => {
+                        let _ = started.send(Err(err));
+                    }
+                }
+            })
+            .map_err(|_| PamError::SystemErr)?;
+
+        started_receiver.await.unwrap_or(Err(PamError::SystemErr))?;
+
+        Ok((Self { requests }, Prompts(prompts_receiver)))
+    }
+
+    /// Authenticate the user with `pam_authenticate`.
+    pub async fn authenticate(&mut self, flags: AuthFlags) -> Result<(), PamError> {
+        self.call(Operation::Authenticate(flags)).await
+    }
+
+    /// Check that the account of the user is valid with `pam_acct_mgmt`.
+    pub async fn acct_mgmt(&mut self, flags: AuthFlags) -> Result<(), PamError> {
+        self.call(Operation::AcctMgmt(flags)).await
+    }
+
+    /// Establish, delete or refresh the credentials of the user with `pam_setcred`.
+    pub async fn setcred(&mut self, flags: CredFlags) -> Result<(), PamError> {
+        self.call(Operation::Setcred(flags)).await
+    }
+
+    /// Open a session with `pam_open_session`.
+    pub async fn open_session(&mut self, flags: SessionFlags) -> Result<(), PamError> {
+        self.call(Operation::OpenSession(flags)).await
+    }
+
+    /// Close the session with `pam_close_session`.
+    pub async fn close_session(&mut self, flags: SessionFlags) -> Result<(), PamError> {
+        self.call(Operation::CloseSession(flags)).await
+    }
+
+    /// Change the authentication token of the user with `pam_chauthtok`.
+    pub async fn chauthtok(&mut self, flags: ChauthtokFlags) -> Result<(), PamError> {
+        self.call(Operation::Chauthtok(flags)).await
+    }
+
+    /// End the transaction with the last status and report whether `pam_end` succeeded.
+    pub async fn end(mut self) -> Result<(), PamError> {
+        self.call(Operation::End).await
+    }
+
+    async fn call(&mut self, operation: Operation) -> Result<(), PamError> {
+        let (reply, result) = oneshot::channel();
+        self.requests
+            .send((operation, reply))
+            .map_err(|_| PamError::SystemErr)?;
+
+        // The thread is gone when it panicked
+        result.await.unwrap_or(Err(PamError::SystemErr))
+    }
+}
+
+/// Make the requested calls until the transaction is ended or the handle is dropped.
+fn run(mut pamh: PamHandle, requests: mpsc::Receiver<Request>) {
+    for (operation, reply) in requests {
+        let result = match operation {
+            Operation::Authenticate(flags) => pamh.authenticate(flags),
+            Operation::AcctMgmt(flags) => pamh.acct_mgmt(flags),
+            Operation::Setcred(flags) => pamh.setcred(flags),
+            Operation::OpenSession(flags) => pamh.open_session(flags),
+            Operation::CloseSession(flags) => pamh.close_session(flags),
+            Operation::Chauthtok(flags) => pamh.chauthtok(flags),
+            Operation::End => {
+                let _ = reply.send(pamh.end());
+                return;
+            }
+        };
+
+        let _ = reply.send(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::ptr;
+
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::response::free_responses;
+    use crate::{pam_message, pam_response, PAM_CONV_ERR, PAM_SUCCESS};
+
+    /// Call the conversation function of `channel` with one message, like libpam.
+    fn converse(channel: &mut Channel, style: c_int, msg: &CStr) -> (c_int, Option<CString>) {
+        let message = pam_message {
+            msg_style: style,
+            msg: msg.as_ptr() as _,
+        };
+        let mut pointer: *const pam_message = &message;
+        let mut resp: *mut pam_response = ptr::null_mut();
+
+        let pam_conv = unsafe { conv::to_pam_conv(channel) };
+        let status = (pam_conv.conv)(1, &mut pointer, &mut resp, pam_conv.app_dataptr);
+        if resp.is_null() {
+            return (status, None);
+        }
+
+        unsafe {
+            let answer = (!(*resp).resp.is_null()).then(|| CStr::from_ptr((*resp).resp).into());
+            free_responses(resp, 1);
+            (status, answer)
+        }
+    }
+
+    fn next(prompts: &mut Prompts) -> Option<Prompt> {
+        block_on(poll_fn(|cx| Pin::new(&mut *prompts).poll_next(cx)))
+    }
+
+    #[test]
+    fn answer() {
+        let (sender, receiver) = stream::unbounded();
+        let mut prompts = Prompts(receiver);
+
+        thread::scope(|scope| {
+            let conversation = scope.spawn(move || {
+                let mut channel = Channel(sender);
+                let info = converse(&mut channel, PAM_TEXT_INFO, c"Welcome");
+                let answer = converse(&mut channel, PAM_PROMPT_ECHO_OFF, c"Password: ");
+                (info, answer)
+            });
+
+            let info = next(&mut prompts).unwrap();
+            assert_eq!((info.style(), info.text()), (PAM_TEXT_INFO, c"Welcome"));
+            assert!(!info.needs_answer());
+
+            let prompt = next(&mut prompts).unwrap();
+            assert_eq!(prompt.style(), PAM_PROMPT_ECHO_OFF);
+            assert!(prompt.needs_answer());
+            prompt.answer(c"hunter2".into());
+
+            let (info, answer) = conversation.join().unwrap();
+            assert_eq!(info, (PAM_SUCCESS, None));
+            assert_eq!(answer, (PAM_SUCCESS, Some(c"hunter2".into())));
+        });
+
+        // The channel was dropped with the conversation
+        assert!(next(&mut prompts).is_none());
+    }
+
+    #[test]
+    fn dropped_prompt() {
+        let (sender, receiver) = stream::unbounded();
+        let mut prompts = Prompts(receiver);
+
+        thread::scope(|scope| {
+            let conversation =
+                scope.spawn(move || converse(&mut Channel(sender), PAM_PROMPT_ECHO_ON, c"login: "));
+
+            drop(next(&mut prompts).unwrap());
+            assert_eq!(conversation.join().unwrap(), (PAM_CONV_ERR, None));
+        });
+    }
+
+    #[test]
+    fn dropped_prompts() {
+        let (sender, receiver) = stream::unbounded();
+        drop(receiver);
+
+        let mut channel = Channel(sender);
+        assert_eq!(
+            converse(&mut channel, PAM_TEXT_INFO, c"Welcome"),
+            (PAM_CONV_ERR, None)
+        );
+        assert_eq!(
+            converse(&mut channel, PAM_PROMPT_ECHO_ON, c"login: "),
+            (PAM_CONV_ERR, None)
+        );
+    }
+
+    #[test]
+    fn dropped_handle() {
+        let Ok((pamh, mut prompts)) = block_on(AsyncPamHandle::start(c"libpam-sys-test", None))
+        else {
+            // Without a usable configuration there is no transaction to end
+            return;
+        };
+
+        // The thread drops the conversation, which ends the stream, once the handle is ended
+        drop(pamh);
+        assert!(next(&mut prompts).is_none());
+    }
+}
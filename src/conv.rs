@@ -0,0 +1,354 @@
+//! Conversation functions written in Rust.
+//!
+//! libpam talks to the user through the conversation function of the application. The
+//! [`Conversation`] trait describes such a function in Rust, and [`to_pam_conv`] turns an
+//! implementation into a [`pam_conv`] that can be passed to `pam_start`.
+//!
+//! The responses are allocated with [`Responses`], because libpam releases them with `free`, and
+//! are wiped when the conversation fails. Errors and panics in the conversation are reported to
+//! libpam as [`PAM_CONV_ERR`].
+//!
+//! ```no_run
+//! use std::ffi::{CStr, CString};
+//! use std::io::stdin;
+//!
+//! use libpam_sys::conv::{Conversation, Result};
+//!
+//! struct Terminal;
+//!
+//! impl Conversation for Terminal {
+//!     fn prompt_echo_on(&mut self, msg: &CStr) -> Result<CString> {
+//!         eprint!("{}", msg.to_string_lossy());
+//!         let mut line = String::new();
+//!         stdin().read_line(&mut line)?;
+//!         Ok(CString::new(line.trim_end_matches('\n'))?)
+//!     }
+//!
+//!     fn prompt_echo_off(&mut self, msg: &CStr) -> Result<CString> {
+//!         // A real application would disable the echo of the terminal here
+//!         self.prompt_echo_on(msg)
+//!     }
+//!
+//!     fn error_msg(&mut self, msg: &CStr) -> Result<()> {
+//!         eprintln!("{}", msg.to_string_lossy());
+//!         Ok(())
+//!     }
+//!
+//!     fn text_info(&mut self, msg: &CStr) -> Result<()> {
+//!         println!("{}", msg.to_string_lossy());
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut terminal = Terminal;
+//! let conv = unsafe { libpam_sys::conv::to_pam_conv(&mut terminal) };
+//! ```
+//!
+//! [`PAM_CONV_ERR`]: crate::PAM_CONV_ERR
+//! [`Responses`]: crate::response::Responses
+
+use std::ffi::{c_int, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+#[cfg(any(docsrs, feature = "linux-pam"))]
+use std::{ffi::c_uint, time::Duration};
+
+use crate::message::Messages;
+use crate::response::{Responses, Secret};
+use crate::{
+    pam_conv, pam_message, pam_response, PAM_BUF_ERR, PAM_CONV_ERR, PAM_ERROR_MSG, PAM_MAX_NUM_MSG,
+    PAM_MAX_RESP_SIZE, PAM_PROMPT_ECHO_OFF, PAM_PROMPT_ECHO_ON, PAM_SUCCESS, PAM_TEXT_INFO,
+};
+
+/// The result of a conversation callback. Any error is reported to libpam as `PAM_CONV_ERR`.
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// The callbacks of a conversation, one for every message style.
+pub trait Conversation {
+    /// Ask the user for a response that is shown while typing.
+    fn prompt_echo_on(&mut self, msg: &CStr) -> Result<CString>;
+
+    /// Ask the user for a response that is hidden while typing, such as a password.
+    fn prompt_echo_off(&mut self, msg: &CStr) -> Result<CString>;
+
+    /// Show an error message to the user.
+    fn error_msg(&mut self, msg: &CStr) -> Result<()>;
+
+    /// Show an informational message to the user.
+    fn text_info(&mut self, msg: &CStr) -> Result<()>;
+
+    /// Ask the user a yes/no/maybe question for a `PAM_RADIO_TYPE` message.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+    fn radio_type(&mut self, msg: &CStr) -> Result<CString> {
+        let _ = msg;
+        Err("PAM_RADIO_TYPE messages are not supported".into())
+    }
+
+    /// Wait after a failed authentication, when the conversation also handles `PAM_FAIL_DELAY`
+    /// through [`fail_delay_fn`].
+    ///
+    /// `status` is the status of the failed call. The default blocks the thread for `delay`, like
+    /// libpam does without a `PAM_FAIL_DELAY` function.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+    fn fail_delay(&mut self, status: c_int, delay: Duration) {
+        let _ = status;
+        std::thread::sleep(delay);
+    }
+
+    /// Answer a binary prompt for a `PAM_BINARY_PROMPT` message, usually with the help of an
+    /// agent.
+    #[cfg(any(docsrs, feature = "pamc"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pamc")))]
+    fn binary_prompt(
+        &mut self,
+        prompt: crate::pamc::BinaryPrompt,
+    ) -> Result<crate::pamc::BinaryPrompt> {
+        let _ = prompt;
+        Err("PAM_BINARY_PROMPT messages are not supported".into())
+    }
+}
+
+macro_rules! forward_conversation {
+    ($($ty:ty),*) => {
+        $(
+            impl<C: Conversation + ?Sized> Conversation for $ty {
+                fn prompt_echo_on(&mut self, msg: &CStr) -> Result<CString> {
+                    (**self).prompt_echo_on(msg)
+                }
+
+                fn prompt_echo_off(&mut self, msg: &CStr) -> Result<CString> {
+                    (**self).prompt_echo_off(msg)
+                }
+
+                fn error_msg(&mut self, msg: &CStr) -> Result<()> {
+                    (**self).error_msg(msg)
+                }
+
+                fn text_info(&mut self, msg: &CStr) -> Result<()> {
+                    (**self).text_info(msg)
+                }
+
+                #[cfg(any(docsrs, feature = "linux-pam"))]
+                fn radio_type(&mut self, msg: &CStr) -> Result<CString> {
+                    (**self).radio_type(msg)
+                }
+
+                #[cfg(any(docsrs, feature = "linux-pam"))]
+                fn fail_delay(&mut self, status: c_int, delay: Duration) {
+                    (**self).fail_delay(status, delay)
+                }
+
+                #[cfg(any(docsrs, feature = "pamc"))]
+                fn binary_prompt(
+                    &mut self,
+                    prompt: crate::pamc::BinaryPrompt,
+                ) -> Result<crate::pamc::BinaryPrompt> {
+                    (**self).binary_prompt(prompt)
+                }
+            }
+        )*
+    };
+}
+
+forward_conversation!(&mut C, Box<C>);
+
+/// A conversation that handles the delay after a failed authentication with a closure.
+///
+/// The closure receives the status of the failed call and the delay, and could for example
+/// schedule a timer instead of blocking the thread. It only replaces the delay of libpam when the
+/// `PAM_FAIL_DELAY` item is set to [`fail_delay_fn`].
+#[cfg(any(docsrs, feature = "linux-pam"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+#[derive(Debug, Clone)]
+pub struct WithFailDelay<C, F> {
+    conversation: C,
+    fail_delay: F,
+}
+
+#[cfg(any(docsrs, feature = "linux-pam"))]
+impl<C: Conversation, F: FnMut(c_int, Duration)> WithFailDelay<C, F> {
+    /// Forward the messages to `conversation` and the delays to `fail_delay`.
+    pub fn new(conversation: C, fail_delay: F) -> Self {
+        Self {
+            conversation,
+            fail_delay,
+        }
+    }
+
+    /// The conversation the messages are forwarded to
+    pub fn conversation(&mut self) -> &mut C {
+        &mut self.conversation
+    }
+}
+
+#[cfg(any(docsrs, feature = "linux-pam"))]
+impl<C: Conversation, F: FnMut(c_int, Duration)> Conversation for WithFailDelay<C, F> {
+    fn prompt_echo_on(&mut self, msg: &CStr) -> Result<CString> {
+        self.conversation.prompt_echo_on(msg)
+    }
+
+    fn prompt_echo_off(&mut self, msg: &CStr) -> Result<CString> {
+        self.conversation.prompt_echo_off(msg)
+    }
+
+    fn error_msg(&mut self, msg: &CStr) -> Result<()> {
+        self.conversation.error_msg(msg)
+    }
+
+    fn text_info(&mut self, msg: &CStr) -> Result<()> {
+        self.conversation.text_info(msg)
+    }
+
+    fn radio_type(&mut self, msg: &CStr) -> Result<CString> {
+        self.conversation.radio_type(msg)
+    }
+
+    fn fail_delay(&mut self, status: c_int, delay: Duration) {
+        (self.fail_delay)(status, delay)
+    }
+
+    #[cfg(any(docsrs, feature = "pamc"))]
+    fn binary_prompt(
+        &mut self,
+        prompt: crate::pamc::BinaryPrompt,
+    ) -> Result<crate::pamc::BinaryPrompt> {
+        self.conversation.binary_prompt(prompt)
+    }
+}
+
+/// Build a [`pam_conv`] that forwards every message to `conversation`.
+///
+/// A `&mut dyn Conversation` can be passed as `&mut &mut dyn Conversation`.
+///
+/// # Safety
+///
+/// The returned [`pam_conv`] points to `conversation`, so it may only be used while
+/// `conversation` is neither moved nor dropped, and is not used otherwise.
+pub unsafe fn to_pam_conv<C: Conversation>(conversation: &mut C) -> pam_conv {
+    pam_conv {
+        conv: trampoline::<C>,
+        app_dataptr: (conversation as *mut C).cast(),
+    }
+}
+
+/// The `PAM_FAIL_DELAY` function that calls [`Conversation::fail_delay`] of the conversation.
+///
+/// [`PamHandle::forward_fail_delay`] sets it safely for the conversation owned by a handle.
+///
+/// # Safety
+///
+/// libpam calls the function with the `appdata_ptr` of the conversation, so it may only be set as
+/// the `PAM_FAIL_DELAY` item of a handle whose `PAM_CONV` item is a [`pam_conv`] created by
+/// [`to_pam_conv`] for a `C`, and only while that conversation is valid.
+///
+/// [`PamHandle::forward_fail_delay`]: crate::handle::PamHandle::forward_fail_delay
+#[cfg(any(docsrs, feature = "linux-pam"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+pub unsafe fn fail_delay_fn<C: Conversation>() -> crate::linux_pam::pam_fail_delay_fn {
+    Some(fail_delay_trampoline::<C>)
+}
+
+/// The `PAM_FAIL_DELAY` function returned by [`fail_delay_fn`].
+#[cfg(any(docsrs, feature = "linux-pam"))]
+unsafe extern "C" fn fail_delay_trampoline<C: Conversation>(
+    status: c_int,
+    usec_delay: c_uint,
+    appdata_ptr: *mut c_void,
+) {
+    if appdata_ptr.is_null() {
+        return;
+    }
+
+    let conversation = &mut *appdata_ptr.cast::<C>();
+    let delay = Duration::from_micros(usec_delay.into());
+
+    // Panics should never unwind into libpam, and there is no status to report them with.
+    let _ = catch_unwind(AssertUnwindSafe(|| conversation.fail_delay(status, delay)));
+}
+
+/// The conversation function of a [`pam_conv`] created by [`to_pam_conv`].
+///
+/// The `appdata_ptr` has to point to a `C`.
+extern "C" fn trampoline<C: Conversation>(
+    num_msg: c_int,
+    msg: *mut *const pam_message,
+    resp: *mut *mut pam_response,
+    appdata_ptr: *mut c_void,
+) -> c_int {
+    if msg.is_null() || resp.is_null() || appdata_ptr.is_null() {
+        return PAM_CONV_ERR;
+    }
+
+    let count = match usize::try_from(num_msg) {
+        Ok(count) if count > 0 && num_msg <= PAM_MAX_NUM_MSG => count,
+        _ => return PAM_CONV_ERR,
+    };
+
+    unsafe {
+        *resp = ptr::null_mut();
+
+        let conversation = &mut *appdata_ptr.cast::<C>();
+        let Ok(mut responses) = Responses::new(count) else {
+            return PAM_BUF_ERR;
+        };
+
+        for (i, message) in Messages::new(num_msg, msg).enumerate() {
+            // Panics should never unwind into libpam. Dropping `responses` wipes the responses
+            // that were already set.
+            match catch_unwind(AssertUnwindSafe(|| respond(conversation, message))) {
+                Ok(Ok(Some(response))) => {
+                    if responses.set(i, &response).is_err() {
+                        return PAM_BUF_ERR;
+                    }
+                }
+                Ok(Ok(None)) => {}
+                Ok(Err(_)) | Err(_) => return PAM_CONV_ERR,
+            }
+        }
+
+        *resp = responses.into_raw();
+    }
+
+    PAM_SUCCESS
+}
+
+/// Call the callback for `message` and return the bytes of its response.
+unsafe fn respond<C: Conversation>(
+    conversation: &mut C,
+    message: Option<&pam_message>,
+) -> Result<Option<Secret>> {
+    let message = message.ok_or("The message is a null pointer")?;
+    if message.msg.is_null() {
+        return Err("The message has no text".into());
+    }
+
+    #[cfg(feature = "pamc")]
+    if message.msg_style == crate::linux_pam::PAM_BINARY_PROMPT {
+        let prompt = crate::pamc::BinaryPrompt::from_raw(message.msg as _)?;
+        let reply = conversation.binary_prompt(prompt)?;
+        return Ok(Some(Secret::from(reply.to_bytes())));
+    }
+
+    let text = CStr::from_ptr(message.msg);
+    match message.msg_style {
+        PAM_PROMPT_ECHO_ON => secret(conversation.prompt_echo_on(text)?),
+        PAM_PROMPT_ECHO_OFF => secret(conversation.prompt_echo_off(text)?),
+        PAM_ERROR_MSG => conversation.error_msg(text).map(|()| None),
+        PAM_TEXT_INFO => conversation.text_info(text).map(|()| None),
+        #[cfg(feature = "linux-pam")]
+        crate::linux_pam::PAM_RADIO_TYPE => secret(conversation.radio_type(text)?),
+        style => Err(format!("Unknown message style {style}").into()),
+    }
+}
+
+/// Take over the bytes of a text response, so they are wiped once they were copied.
+fn secret(response: CString) -> Result<Option<Secret>> {
+    let response = Secret::from(response.into_bytes());
+    if response.len() >= PAM_MAX_RESP_SIZE as usize {
+        return Err("The response is longer than PAM_MAX_RESP_SIZE".into());
+    }
+
+    Ok(Some(response))
+}
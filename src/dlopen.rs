@@ -0,0 +1,308 @@
+//! Runtime loading of libpam.
+//!
+//! With the `dlopen` feature, libpam is not linked when the binary is built. Instead, the library
+//! is opened the first time one of the PAM functions is called and every symbol is resolved the
+//! first time it is used. This allows a binary to start on hosts without libpam.
+//!
+//! Functions that return a status code return [`PAM_OPEN_ERR`] when the library cannot be loaded
+//! and [`PAM_SYMBOL_ERR`] when the symbol is missing. Functions that return a pointer return a null
+//! pointer instead. Functions without a return value have no way to report the error, and variadic
+//! functions and statics cannot be wrapped, so these panic when they are not available. Use
+//! [`load`] or [`is_available`] to check beforehand and get the [`LoadError`].
+//!
+//! Mutable statics are not `static mut` with this feature, so they cannot be assigned directly.
+//! [`static_mut_ptr!`] returns a pointer to them that works with and without this feature.
+//!
+//! The functions of the `misc` and `pamc` features live in libpam_misc and libpamc. These are
+//! opened separately and can be checked with [`LIBPAM_MISC`] and [`LIBPAMC`].
+//!
+//! [`PAM_OPEN_ERR`]: crate::PAM_OPEN_ERR
+//! [`PAM_SYMBOL_ERR`]: crate::PAM_SYMBOL_ERR
+//! [`static_mut_ptr!`]: crate::static_mut_ptr
+
+use std::ffi::c_int;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use libloading::Library;
+
+/// The file names that are attempted, in order, when opening libpam.
+#[cfg(target_os = "macos")]
+pub const LIBRARY_NAMES: &[&str] = &["libpam.2.dylib", "libpam.dylib"];
+/// The file names that are attempted, in order, when opening libpam.
+#[cfg(not(target_os = "macos"))]
+pub const LIBRARY_NAMES: &[&str] = &["libpam.so.0", "libpam.so.6", "libpam.so"];
+
+/// The file names that are attempted, in order, when opening libpam_misc.
+#[cfg(any(docsrs, feature = "misc"))]
+pub const MISC_LIBRARY_NAMES: &[&str] = &["libpam_misc.so.0", "libpam_misc.so"];
+
+/// The file names that are attempted, in order, when opening libpamc.
+#[cfg(any(docsrs, feature = "pamc"))]
+pub const PAMC_LIBRARY_NAMES: &[&str] = &["libpamc.so.0", "libpamc.so"];
+
+/// libpam
+pub static LIBPAM: SharedLibrary = SharedLibrary::new("libpam", LIBRARY_NAMES);
+
+/// libpam_misc, which contains the functions of the `misc` feature
+#[cfg(any(docsrs, feature = "misc"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "misc")))]
+pub static LIBPAM_MISC: SharedLibrary = SharedLibrary::new("libpam_misc", MISC_LIBRARY_NAMES);
+
+/// libpamc, which contains the functions of the `pamc` feature
+#[cfg(any(docsrs, feature = "pamc"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "pamc")))]
+pub static LIBPAMC: SharedLibrary = SharedLibrary::new("libpamc", PAMC_LIBRARY_NAMES);
+
+/// An error that occurred while loading a library or one of its symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// None of the file names of the library could be opened
+    Library { name: &'static str, reason: String },
+    /// The library was opened, but it does not contain the symbol
+    Symbol {
+        library: &'static str,
+        name: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Library { name, reason } => write!(f, "Failed to load {name}: {reason}"),
+            Self::Symbol {
+                library,
+                name,
+                reason,
+            } => {
+                write!(f, "Failed to find `{name}` in {library}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// A shared library that is opened the first time it is used.
+pub struct SharedLibrary {
+    name: &'static str,
+    file_names: &'static [&'static str],
+    library: OnceLock<Result<Library, LoadError>>,
+}
+
+impl SharedLibrary {
+    /// A library that is opened from the first of `file_names` that can be loaded.
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, file_names: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            file_names,
+            library: OnceLock::new(),
+        }
+    }
+
+    /// Get the loaded library, loading it if that has not happened yet.
+    pub fn get(&self) -> Result<&Library, &LoadError> {
+        self.library
+            .get_or_init(|| {
+                let mut reasons = Vec::with_capacity(self.file_names.len());
+
+                for file_name in self.file_names {
+                    match unsafe { Library::new(file_name) } {
+                        Ok(library) => return Ok(library),
+                        Err(err) => reasons.push(err.to_string()),
+                    }
+                }
+
+                Err(LoadError::Library {
+                    name: self.name,
+                    reason: reasons.join("; "),
+                })
+            })
+            .as_ref()
+    }
+
+    /// Load the library, if that has not happened yet.
+    pub fn load(&self) -> Result<(), LoadError> {
+        self.get().map(|_| ()).map_err(Clone::clone)
+    }
+
+    /// Returns whether the library can be loaded and exports the symbol `name`.
+    pub fn is_available(&self, name: &str) -> bool {
+        self.get()
+            .is_ok_and(|library| unsafe { library.get::<*const ()>(name.as_bytes()) }.is_ok())
+    }
+}
+
+/// Get the loaded libpam, loading it if that has not happened yet.
+pub fn library() -> Result<&'static Library, &'static LoadError> {
+    LIBPAM.get()
+}
+
+/// Load libpam, if that has not happened yet.
+pub fn load() -> Result<(), LoadError> {
+    LIBPAM.load()
+}
+
+/// Returns whether libpam can be loaded and exports the symbol `name`.
+pub fn is_available(name: &str) -> bool {
+    LIBPAM.is_available(name)
+}
+
+/// A lazily resolved symbol of a [`SharedLibrary`].
+///
+/// # Panics
+///
+/// Dereferencing panics when the symbol cannot be resolved. [`Symbol::get`] returns the error
+/// instead.
+#[doc(hidden)]
+pub struct Symbol<T> {
+    library: &'static SharedLibrary,
+    name: &'static str,
+    value: OnceLock<Result<T, LoadError>>,
+}
+
+impl<T: Copy> Symbol<T> {
+    pub const fn new(library: &'static SharedLibrary, name: &'static str) -> Self {
+        Self {
+            library,
+            name,
+            value: OnceLock::new(),
+        }
+    }
+
+    pub fn get(&self) -> Result<T, &LoadError> {
+        self.value
+            .get_or_init(|| {
+                let library = self.library.get().map_err(Clone::clone)?;
+                let name = self.name.trim_end_matches('\0');
+
+                unsafe { library.get::<T>(self.name.as_bytes()) }
+                    .map(|symbol| *symbol)
+                    .map_err(|err| LoadError::Symbol {
+                        library: self.library.name,
+                        name,
+                        reason: err.to_string(),
+                    })
+            })
+            .as_ref()
+            .copied()
+    }
+}
+
+impl<T: Copy> Deref for Symbol<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        if let Err(err) = self.get() {
+            panic!("{err}");
+        }
+
+        // The value was just initialized successfully
+        match self.value.get() {
+            Some(Ok(value)) => value,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// A lazily resolved static variable of a [`SharedLibrary`].
+///
+/// # Panics
+///
+/// Dereferencing panics when the symbol cannot be resolved.
+#[doc(hidden)]
+pub struct Static<T: 'static> {
+    address: Symbol<Address<T>>,
+}
+
+/// A lazily resolved mutable static variable of a [`SharedLibrary`].
+///
+/// # Panics
+///
+/// [`StaticMut::as_ptr`] panics when the symbol cannot be resolved.
+#[doc(hidden)]
+pub struct StaticMut<T: 'static> {
+    address: Symbol<Address<T>>,
+}
+
+// Resolved in place of the raw pointer, so it has to share its layout
+#[repr(transparent)]
+struct Address<T: 'static>(*mut T);
+
+impl<T> Clone for Address<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Address<T> {}
+
+// The static is owned by libpam, which stays loaded for the rest of the program.
+unsafe impl<T> Send for Address<T> {}
+unsafe impl<T> Sync for Address<T> {}
+
+impl<T: 'static> Static<T> {
+    pub const fn new(library: &'static SharedLibrary, name: &'static str) -> Self {
+        Self {
+            address: Symbol::new(library, name),
+        }
+    }
+}
+
+impl<T: 'static> Deref for Static<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.address.0 }
+    }
+}
+
+impl<T: 'static> StaticMut<T> {
+    pub const fn new(library: &'static SharedLibrary, name: &'static str) -> Self {
+        Self {
+            address: Symbol::new(library, name),
+        }
+    }
+
+    /// The address of the variable, which stays valid for the rest of the program.
+    pub fn as_ptr(&self) -> *mut T {
+        self.address.0
+    }
+}
+
+/// The value a wrapped function returns when libpam or the symbol is not available.
+#[doc(hidden)]
+pub trait MissingSymbol {
+    fn missing(err: &LoadError) -> Self;
+}
+
+impl MissingSymbol for c_int {
+    fn missing(err: &LoadError) -> Self {
+        match err {
+            LoadError::Library { .. } => crate::PAM_OPEN_ERR,
+            LoadError::Symbol { .. } => crate::PAM_SYMBOL_ERR,
+        }
+    }
+}
+
+/// There is no value to report the error with, and doing nothing would hide it.
+impl MissingSymbol for () {
+    fn missing(err: &LoadError) -> Self {
+        panic!("{err}");
+    }
+}
+
+impl<T> MissingSymbol for *const T {
+    fn missing(_: &LoadError) -> Self {
+        std::ptr::null()
+    }
+}
+
+impl<T> MissingSymbol for *mut T {
+    fn missing(_: &LoadError) -> Self {
+        std::ptr::null_mut()
+    }
+}
@@ -0,0 +1,163 @@
+//! The status codes of libpam as a Rust error.
+//!
+//! [`PamError`] has a variant for every status code other than [`PAM_SUCCESS`] of the
+//! implementation this crate was built for. Its messages are built in, so formatting an error does
+//! not need `pam_strerror` or a handle.
+//!
+//! ```
+//! use libpam_sys::error::PamError;
+//! use libpam_sys::{PAM_AUTH_ERR, PAM_SUCCESS};
+//!
+//! assert_eq!(PamError::check(PAM_SUCCESS), Ok(()));
+//! assert_eq!(PamError::check(PAM_AUTH_ERR), Err(PamError::AuthErr));
+//! assert_eq!(PamError::AuthErr.to_string(), "Authentication failure");
+//! ```
+
+use std::ffi::c_int;
+use std::fmt;
+
+use crate::PAM_SUCCESS;
+
+/// Defines [`PamError`] with a variant, a status code and a message for every error.
+macro_rules! pam_errors {
+    ($($(#[$attr:meta])* $variant:ident = $code:path => $message:literal,)*) => {
+        /// A status code other than [`PAM_SUCCESS`] returned by libpam or a module.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum PamError {
+            $(
+                $(#[$attr])*
+                #[doc = $message]
+                $variant,
+            )*
+        }
+
+        impl PamError {
+            /// The status code of the error
+            pub const fn code(self) -> c_int {
+                match self {
+                    $(
+                        $(#[$attr])*
+                        Self::$variant => $code,
+                    )*
+                }
+            }
+
+            /// The error with the status code `code`, or `None` for [`PAM_SUCCESS`] and unknown
+            /// codes
+            pub const fn from_code(code: c_int) -> Option<Self> {
+                $(
+                    $(#[$attr])*
+                    if code == $code {
+                        return Some(Self::$variant);
+                    }
+                )*
+
+                None
+            }
+
+            /// The description of the error
+            pub const fn message(self) -> &'static str {
+                match self {
+                    $(
+                        $(#[$attr])*
+                        Self::$variant => $message,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+pam_errors! {
+    OpenErr = crate::PAM_OPEN_ERR => "Failed to load module",
+    SymbolErr = crate::PAM_SYMBOL_ERR => "Symbol not found",
+    ServiceErr = crate::PAM_SERVICE_ERR => "Error in service module",
+    SystemErr = crate::PAM_SYSTEM_ERR => "System error",
+    BufErr = crate::PAM_BUF_ERR => "Memory buffer error",
+    PermDenied = crate::PAM_PERM_DENIED => "Permission denied",
+    AuthErr = crate::PAM_AUTH_ERR => "Authentication failure",
+    CredInsufficient = crate::PAM_CRED_INSUFFICIENT
+        => "Insufficient credentials to access authentication data",
+    AuthinfoUnavail = crate::PAM_AUTHINFO_UNAVAIL
+        => "Authentication service cannot retrieve authentication info",
+    UserUnknown = crate::PAM_USER_UNKNOWN
+        => "User not known to the underlying authentication module",
+    Maxtries = crate::PAM_MAXTRIES => "Have exhausted maximum number of retries for service",
+    NewAuthtokReqd = crate::PAM_NEW_AUTHTOK_REQD
+        => "Authentication token is no longer valid; new one required",
+    AcctExpired = crate::PAM_ACCT_EXPIRED => "User account has expired",
+    SessionErr = crate::PAM_SESSION_ERR
+        => "Cannot make/remove an entry for the specified session",
+    CredUnavail = crate::PAM_CRED_UNAVAIL
+        => "Authentication service cannot retrieve user credentials",
+    CredExpired = crate::PAM_CRED_EXPIRED => "User credentials expired",
+    CredErr = crate::PAM_CRED_ERR => "Failure setting user credentials",
+    NoModuleData = crate::PAM_NO_MODULE_DATA => "No module specific data is present",
+    ConvErr = crate::PAM_CONV_ERR => "Conversation error",
+    AuthtokErr = crate::PAM_AUTHTOK_ERR => "Authentication token manipulation error",
+    AuthtokRecoveryErr = crate::PAM_AUTHTOK_RECOVERY_ERR
+        => "Authentication information cannot be recovered",
+    AuthtokLockBusy = crate::PAM_AUTHTOK_LOCK_BUSY => "Authentication token lock busy",
+    AuthtokDisableAging = crate::PAM_AUTHTOK_DISABLE_AGING
+        => "Authentication token aging disabled",
+    TryAgain = crate::PAM_TRY_AGAIN => "Failed preliminary check by password service",
+    Ignore = crate::PAM_IGNORE => "The return value should be ignored by PAM dispatch",
+    Abort = crate::PAM_ABORT => "Critical error - immediate abort",
+    AuthtokExpired = crate::PAM_AUTHTOK_EXPIRED => "Authentication token expired",
+    ModuleUnknown = crate::PAM_MODULE_UNKNOWN => "Module is unknown",
+    BadItem = crate::PAM_BAD_ITEM => "Bad item passed to pam_*_item()",
+
+    #[cfg(pam_impl = "linux-pam")]
+    ConvAgain = crate::linux_pam::PAM_CONV_AGAIN => "Conversation is waiting for event",
+    #[cfg(pam_impl = "linux-pam")]
+    Incomplete = crate::linux_pam::PAM_INCOMPLETE => "Application needs to call libpam again",
+
+    #[cfg(pam_impl = "openpam")]
+    DomainUnknown = crate::openpam::PAM_DOMAIN_UNKNOWN => "Unknown authentication domain",
+    #[cfg(pam_impl = "openpam")]
+    BadHandle = crate::openpam::PAM_BAD_HANDLE => "Invalid PAM handle",
+    #[cfg(pam_impl = "openpam")]
+    BadFeature = crate::openpam::PAM_BAD_FEATURE => "Unrecognized or restricted feature",
+    #[cfg(pam_impl = "openpam")]
+    BadConstant = crate::openpam::PAM_BAD_CONSTANT => "Bad constant",
+}
+
+impl PamError {
+    /// Returns `Ok` for [`PAM_SUCCESS`] and the error for any other status.
+    ///
+    /// Unknown status codes are reported as [`PamError::SystemErr`].
+    pub const fn check(status: c_int) -> Result<(), Self> {
+        if status == PAM_SUCCESS {
+            return Ok(());
+        }
+
+        match Self::from_code(status) {
+            Some(err) => Err(err),
+            None => Err(Self::SystemErr),
+        }
+    }
+}
+
+impl TryFrom<c_int> for PamError {
+    /// The status code, when it is [`PAM_SUCCESS`] or unknown
+    type Error = c_int;
+
+    fn try_from(code: c_int) -> Result<Self, c_int> {
+        Self::from_code(code).ok_or(code)
+    }
+}
+
+impl From<PamError> for c_int {
+    fn from(err: PamError) -> Self {
+        err.code()
+    }
+}
+
+impl fmt::Display for PamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for PamError {}
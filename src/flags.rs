@@ -0,0 +1,110 @@
+//! The flags of every PAM call as their own type.
+//!
+//! The flags are plain `c_int`s in libpam, and Linux-PAM and OpenPAM give them different values.
+//! For example, `PAM_ESTABLISH_CRED` is `0x2` in Linux-PAM and `0x1` in OpenPAM. Each type here
+//! only contains the flags that are valid for its calls, with the values of the implementation this
+//! crate was built for, so [`bits`](AuthFlags::bits) is what libpam expects.
+//!
+//! ```
+//! use libpam_sys::flags::{AuthFlags, ChauthtokFlags, CredFlags};
+//!
+//! let flags = AuthFlags::SILENT | AuthFlags::DISALLOW_NULL_AUTHTOK;
+//! assert_eq!(flags.bits(), libpam_sys::PAM_SILENT | libpam_sys::PAM_DISALLOW_NULL_AUTHTOK);
+//!
+//! assert!(CredFlags::ESTABLISH_CRED.is_valid());
+//! assert!(!(CredFlags::ESTABLISH_CRED | CredFlags::DELETE_CRED).is_valid());
+//! assert!(!ChauthtokFlags::UPDATE_AUTHTOK.is_valid());
+//! ```
+
+use std::ffi::c_int;
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// The flags of `pam_authenticate` and `pam_acct_mgmt`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct AuthFlags: c_int {
+        /// `PAM_SILENT`, the modules should not generate any messages
+        const SILENT = crate::PAM_SILENT;
+        /// `PAM_DISALLOW_NULL_AUTHTOK`, fail if the user has an empty authentication token
+        const DISALLOW_NULL_AUTHTOK = crate::PAM_DISALLOW_NULL_AUTHTOK;
+    }
+
+    /// The flags of `pam_setcred`, which takes at most one of the credential actions
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct CredFlags: c_int {
+        /// `PAM_SILENT`, the modules should not generate any messages
+        const SILENT = crate::PAM_SILENT;
+        /// `PAM_ESTABLISH_CRED`, set the credentials of the user
+        const ESTABLISH_CRED = crate::PAM_ESTABLISH_CRED;
+        /// `PAM_DELETE_CRED`, delete the credentials of the user
+        const DELETE_CRED = crate::PAM_DELETE_CRED;
+        /// `PAM_REINITIALIZE_CRED`, reinitialize the credentials of the user
+        const REINITIALIZE_CRED = crate::PAM_REINITIALIZE_CRED;
+        /// `PAM_REFRESH_CRED`, extend the lifetime of the credentials of the user
+        const REFRESH_CRED = crate::PAM_REFRESH_CRED;
+    }
+
+    /// The flags of `pam_chauthtok`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ChauthtokFlags: c_int {
+        /// `PAM_SILENT`, the modules should not generate any messages
+        const SILENT = crate::PAM_SILENT;
+        /// `PAM_CHANGE_EXPIRED_AUTHTOK`, only change the authentication token if it has expired
+        const CHANGE_EXPIRED_AUTHTOK = crate::PAM_CHANGE_EXPIRED_AUTHTOK;
+        /// `PAM_PRELIM_CHECK`, which libpam passes to modules for the preliminary checks
+        const PRELIM_CHECK = crate::PAM_PRELIM_CHECK;
+        /// `PAM_UPDATE_AUTHTOK`, which libpam passes to modules to update the token
+        const UPDATE_AUTHTOK = crate::PAM_UPDATE_AUTHTOK;
+    }
+
+    /// The flags of `pam_open_session` and `pam_close_session`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SessionFlags: c_int {
+        /// `PAM_SILENT`, the modules should not generate any messages
+        const SILENT = crate::PAM_SILENT;
+    }
+}
+
+impl CredFlags {
+    /// The credential actions, of which `pam_setcred` takes at most one
+    pub const ACTIONS: Self = Self::ESTABLISH_CRED
+        .union(Self::DELETE_CRED)
+        .union(Self::REINITIALIZE_CRED)
+        .union(Self::REFRESH_CRED);
+
+    /// Returns whether at most one credential action is set.
+    ///
+    /// Linux-PAM establishes the credentials when no flag is set at all. With only
+    /// [`CredFlags::SILENT`], the modules receive no action.
+    pub const fn is_valid(self) -> bool {
+        self.intersection(Self::ACTIONS).bits().count_ones() <= 1
+    }
+}
+
+impl ChauthtokFlags {
+    /// The flags that libpam passes to modules, and fails `pam_chauthtok` with when an application
+    /// passes them
+    pub const MODULE_ONLY: Self = Self::PRELIM_CHECK.union(Self::UPDATE_AUTHTOK);
+
+    /// Returns whether an application may pass the flags, which is when none of
+    /// [`ChauthtokFlags::MODULE_ONLY`] is set.
+    pub const fn is_valid(self) -> bool {
+        !self.intersects(Self::MODULE_ONLY)
+    }
+}
+
+/// Implements the conversion into the `c_int` passed to libpam.
+macro_rules! into_c_int {
+    ($($flags:ident),*) => {
+        $(
+            impl From<$flags> for c_int {
+                fn from(flags: $flags) -> Self {
+                    flags.bits()
+                }
+            }
+        )*
+    };
+}
+
+into_c_int!(AuthFlags, CredFlags, ChauthtokFlags, SessionFlags);
@@ -0,0 +1,321 @@
+//! A safe owner of a PAM transaction.
+//!
+//! [`PamHandle`] wraps the handle returned by `pam_start`. It remembers the status of the last
+//! call, so that `pam_end` receives the status of the transaction when the handle is dropped.
+//!
+//! ```no_run
+//! use std::ffi::{c_int, c_void};
+//! use std::ptr;
+//!
+//! use libpam_sys::flags::AuthFlags;
+//! use libpam_sys::handle::PamHandle;
+//! use libpam_sys::{pam_conv, pam_message, pam_response, PAM_CONV_ERR};
+//!
+//! extern "C" fn conversation(
+//!     _num_msg: c_int,
+//!     _msg: *mut *const pam_message,
+//!     _resp: *mut *mut pam_response,
+//!     _appdata_ptr: *mut c_void,
+//! ) -> c_int {
+//!     PAM_CONV_ERR
+//! }
+//!
+//! let conv = pam_conv {
+//!     conv: conversation,
+//!     app_dataptr: ptr::null_mut(),
+//! };
+//!
+//! let mut pamh = PamHandle::start(c"login", Some(c"user"), &conv)?;
+//! pamh.authenticate(AuthFlags::empty())?;
+//! pamh.acct_mgmt(AuthFlags::DISALLOW_NULL_AUTHTOK)?;
+//! # Ok::<(), libpam_sys::error::PamError>(())
+//! ```
+
+use std::ffi::{c_int, CStr};
+use std::fmt;
+use std::ptr;
+
+#[cfg(feature = "conv")]
+use crate::conv::{self, Conversation};
+use crate::error::PamError;
+use crate::flags::{AuthFlags, ChauthtokFlags, CredFlags, SessionFlags};
+use crate::item::{self, CopiedItem, Item};
+use crate::{
+    pam_acct_mgmt, pam_authenticate, pam_chauthtok, pam_close_session, pam_conv, pam_end,
+    pam_handle_t, pam_open_session, pam_setcred, pam_start, PAM_SUCCESS,
+};
+
+/// An active PAM transaction, which is ended when this is dropped.
+pub struct PamHandle {
+    pamh: *mut pam_handle_t,
+    status: c_int,
+    /// The conversation that is owned by the handle, or null
+    #[cfg(feature = "conv")]
+    conversation: *mut Box<dyn Conversation>,
+}
+
+impl fmt::Debug for PamHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PamHandle")
+            .field("pamh", &self.pamh)
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PamHandle {
+    /// Start a transaction for `service` with `pam_start`.
+    ///
+    /// libpam copies `conv`, but not the data its `app_dataptr` points to. That data has to stay
+    /// valid for as long as the handle exists.
+    pub fn start(service: &CStr, user: Option<&CStr>, conv: &pam_conv) -> Result<Self, PamError> {
+        let mut pamh = ptr::null_mut();
+        let status = unsafe {
+            pam_start(
+                service.as_ptr(),
+                user.map_or(ptr::null(), CStr::as_ptr),
+                conv,
+                &mut pamh,
+            )
+        };
+
+        Self::from_start(pamh, status)
+    }
+
+    /// Start a transaction with `pam_start_confdir`, which reads the configuration from
+    /// `conf_dir` instead of the system configuration.
+    ///
+    /// See [`PamHandle::start`] for the requirements on `conv`.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+    pub fn start_confdir(
+        service: &CStr,
+        user: Option<&CStr>,
+        conv: &pam_conv,
+        conf_dir: &CStr,
+    ) -> Result<Self, PamError> {
+        let mut pamh = ptr::null_mut();
+        let status = unsafe {
+            crate::linux_pam::pam_start_confdir(
+                service.as_ptr(),
+                user.map_or(ptr::null(), CStr::as_ptr),
+                conv,
+                conf_dir.as_ptr(),
+                &mut pamh,
+            )
+        };
+
+        Self::from_start(pamh, status)
+    }
+
+    /// Start a transaction for `service` with `pam_start`, which talks to the user through
+    /// `conversation`.
+    ///
+    /// The conversation is owned by the handle and dropped after `pam_end` was called.
+    #[cfg(any(docsrs, feature = "conv"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "conv")))]
+    pub fn start_with(
+        service: &CStr,
+        user: Option<&CStr>,
+        conversation: impl Conversation + 'static,
+    ) -> Result<Self, PamError> {
+        let conversation: Box<dyn Conversation> = Box::new(conversation);
+        let conversation = Box::into_raw(Box::new(conversation));
+
+        // The conversation is only freed when the handle is dropped
+        let conv = unsafe { conv::to_pam_conv(&mut *conversation) };
+        let result = Self::start(service, user, &conv);
+
+        match result {
+            Ok(mut pamh) => {
+                pamh.conversation = conversation;
+                Ok(pamh)
+            }
+            Err(err) => {
+                drop(unsafe { Box::from_raw(conversation) });
+                Err(err)
+            }
+        }
+    }
+
+    /// Let the conversation of [`PamHandle::start_with`] handle the delay after a failed
+    /// authentication, by setting `PAM_FAIL_DELAY` to [`conv::fail_delay_fn`].
+    ///
+    /// Fails with [`PamError::SystemErr`] when the handle does not own the conversation.
+    #[cfg(any(docsrs, all(feature = "conv", feature = "linux-pam")))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "conv", feature = "linux-pam"))))]
+    pub fn forward_fail_delay(&mut self) -> Result<(), PamError> {
+        if self.conversation.is_null() {
+            return Err(PamError::SystemErr);
+        }
+
+        // The function reads the conversation of the handle, which lives as long as the handle
+        unsafe {
+            let fail_delay = conv::fail_delay_fn::<Box<dyn Conversation>>();
+            self.set_item_unchecked::<item::FailDelay>(fail_delay)
+        }
+    }
+
+    fn from_start(pamh: *mut pam_handle_t, status: c_int) -> Result<Self, PamError> {
+        // Both implementations release the handle themselves when starting fails
+        PamError::check(status)?;
+
+        if pamh.is_null() {
+            return Err(PamError::SystemErr);
+        }
+
+        Ok(Self {
+            pamh,
+            status,
+            #[cfg(feature = "conv")]
+            conversation: ptr::null_mut(),
+        })
+    }
+
+    /// The raw handle, for functions that are not wrapped.
+    ///
+    /// The handle stays owned by `self`, so it must not be passed to `pam_end`.
+    pub fn as_ptr(&self) -> *mut pam_handle_t {
+        self.pamh
+    }
+
+    /// The status of the last call, which is passed to `pam_end`
+    pub fn status(&self) -> c_int {
+        self.status
+    }
+
+    /// Record the status of a call to a function that is not wrapped.
+    pub fn set_status(&mut self, status: c_int) -> Result<(), PamError> {
+        self.status = status;
+        PamError::check(status)
+    }
+
+    /// Read the item `I` with `pam_get_item`.
+    ///
+    /// The value is owned by libpam and borrows the handle, so it cannot outlive a change of the
+    /// item through [`PamHandle::set_item`].
+    pub fn get_item<I: Item>(&self) -> Result<I::Value<'_>, PamError> {
+        unsafe { item::get_item::<I>(self.pamh) }
+    }
+
+    /// Set the item `I` with `pam_set_item`.
+    ///
+    /// Only items that libpam copies completely can be set safely. The others, like `PAM_CONV`,
+    /// are set with [`PamHandle::set_item_unchecked`].
+    ///
+    /// ```compile_fail
+    /// # use libpam_sys::handle::PamHandle;
+    /// # use libpam_sys::item::Conv;
+    /// # fn set(pamh: &mut PamHandle, conv: &libpam_sys::pam_conv) {
+    /// // libpam keeps the `app_dataptr` of the conversation
+    /// pamh.set_item::<Conv>(Some(conv));
+    /// # }
+    /// ```
+    pub fn set_item<I: CopiedItem>(&mut self, value: I::Value<'_>) -> Result<(), PamError> {
+        unsafe { item::set_item::<I>(self.pamh, value) }
+    }
+
+    /// Set the item `I` with `pam_set_item`, also when libpam keeps pointers of the value.
+    ///
+    /// # Safety
+    ///
+    /// Everything the value points to and libpam does not copy, like the `app_dataptr` of
+    /// `PAM_CONV` or the state read by a `PAM_FAIL_DELAY` function, has to stay valid for as long
+    /// as the item is set.
+    pub unsafe fn set_item_unchecked<I: Item>(
+        &mut self,
+        value: I::Value<'_>,
+    ) -> Result<(), PamError> {
+        item::set_item::<I>(self.pamh, value)
+    }
+
+    /// Set the `PAM_XAUTHDATA` item to a copy of `xauth`.
+    ///
+    /// The raw struct is not accepted by [`PamHandle::set_item`], because libpam copies through
+    /// its pointers and lengths.
+    ///
+    /// ```compile_fail
+    /// # use libpam_sys::handle::PamHandle;
+    /// # use libpam_sys::item::XAuthData;
+    /// # fn set(pamh: &mut PamHandle) {
+    /// pamh.set_item::<XAuthData>(None);
+    /// # }
+    /// ```
+    #[cfg(any(docsrs, pam_impl = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+    pub fn set_xauth(&mut self, xauth: &crate::xauth::XAuth) -> Result<(), PamError> {
+        unsafe { xauth.set(self.pamh) }
+    }
+
+    /// Authenticate the user with `pam_authenticate`.
+    pub fn authenticate(&mut self, flags: AuthFlags) -> Result<(), PamError> {
+        self.set_status(unsafe { pam_authenticate(self.pamh, flags.bits()) })
+    }
+
+    /// Check that the account of the user is valid with `pam_acct_mgmt`.
+    pub fn acct_mgmt(&mut self, flags: AuthFlags) -> Result<(), PamError> {
+        self.set_status(unsafe { pam_acct_mgmt(self.pamh, flags.bits()) })
+    }
+
+    /// Establish, delete or refresh the credentials of the user with `pam_setcred`.
+    ///
+    /// Fails with [`PamError::SystemErr`] without calling libpam when `flags` contain more than
+    /// one credential action.
+    pub fn setcred(&mut self, flags: CredFlags) -> Result<(), PamError> {
+        if !flags.is_valid() {
+            return Err(PamError::SystemErr);
+        }
+
+        self.set_status(unsafe { pam_setcred(self.pamh, flags.bits()) })
+    }
+
+    /// Open a session with `pam_open_session`.
+    pub fn open_session(&mut self, flags: SessionFlags) -> Result<(), PamError> {
+        self.set_status(unsafe { pam_open_session(self.pamh, flags.bits()) })
+    }
+
+    /// Close the session with `pam_close_session`.
+    pub fn close_session(&mut self, flags: SessionFlags) -> Result<(), PamError> {
+        self.set_status(unsafe { pam_close_session(self.pamh, flags.bits()) })
+    }
+
+    /// Change the authentication token of the user with `pam_chauthtok`.
+    ///
+    /// Fails with [`PamError::SystemErr`] without calling libpam when `flags` contain one of
+    /// [`ChauthtokFlags::MODULE_ONLY`], which libpam rejects as well.
+    pub fn chauthtok(&mut self, flags: ChauthtokFlags) -> Result<(), PamError> {
+        if !flags.is_valid() {
+            return Err(PamError::SystemErr);
+        }
+
+        self.set_status(unsafe { pam_chauthtok(self.pamh, flags.bits()) })
+    }
+
+    /// End the transaction with the last status and report whether `pam_end` succeeded.
+    pub fn end(mut self) -> Result<(), PamError> {
+        PamError::check(self.end_transaction())
+    }
+
+    fn end_transaction(&mut self) -> c_int {
+        if self.pamh.is_null() {
+            return PAM_SUCCESS;
+        }
+
+        let status = unsafe { pam_end(self.pamh, self.status) };
+        self.pamh = ptr::null_mut();
+
+        #[cfg(feature = "conv")]
+        if !self.conversation.is_null() {
+            drop(unsafe { Box::from_raw(self.conversation) });
+            self.conversation = ptr::null_mut();
+        }
+
+        status
+    }
+}
+
+impl Drop for PamHandle {
+    fn drop(&mut self) {
+        self.end_transaction();
+    }
+}
@@ -0,0 +1,161 @@
+//! The PAM implementation and version this crate was built for.
+//!
+//! The build script resolves the implementation from the features, the environment or the
+//! installed library, and the version from pkg-config, the bundled sources of the `vendored`
+//! feature or the symbol versions of Linux-PAM. [`IMPLEMENTATION`] and [`PAM_VERSION`] expose the result,
+//! so it does not have to be detected again. Build scripts of dependents receive the same values
+//! as `DEP_PAM_IMPL` and `DEP_PAM_VERSION`.
+//!
+//! ```
+//! use libpam_sys::implementation::PamImplementation;
+//! use libpam_sys::{IMPLEMENTATION, PAM_VERSION};
+//!
+//! match IMPLEMENTATION {
+//!     PamImplementation::LinuxPam => println!("Built for Linux-PAM"),
+//!     PamImplementation::OpenPam => println!("Built for OpenPAM"),
+//! }
+//!
+//! if let Some(version) = PAM_VERSION {
+//!     println!("Version {version}");
+//! }
+//! ```
+
+use std::fmt;
+
+/// An implementation of PAM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PamImplementation {
+    /// [Linux-PAM](https://github.com/linux-pam/linux-pam)
+    LinuxPam,
+    /// [OpenPAM](https://www.openpam.org/)
+    OpenPam,
+}
+
+impl PamImplementation {
+    /// The name of the implementation, like `Linux-PAM`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::LinuxPam => "Linux-PAM",
+            Self::OpenPam => "OpenPAM",
+        }
+    }
+}
+
+impl fmt::Display for PamImplementation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The version of a PAM library.
+///
+/// OpenPAM releases are numbered by date, so their version is only a `major` like `20190224`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PamVersion {
+    /// The major version
+    pub major: u32,
+    /// The minor version
+    pub minor: u32,
+    /// The patch version
+    pub patch: u32,
+}
+
+impl PamVersion {
+    /// The version `major.minor.patch`
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a version like `1.5.2`, where the minor and patch version may be left out.
+    ///
+    /// Returns `None` for anything else, such as a suffix after the numbers.
+    pub const fn parse(version: &str) -> Option<Self> {
+        let bytes = version.as_bytes();
+        let mut parts = [0u32; 3];
+        let mut part = 0;
+        let mut digits = 0;
+
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'.' if digits > 0 && part < 2 => {
+                    part += 1;
+                    digits = 0;
+                }
+                byte @ b'0'..=b'9' => {
+                    let Some(value) = parts[part].checked_mul(10) else {
+                        return None;
+                    };
+                    let Some(value) = value.checked_add((byte - b'0') as u32) else {
+                        return None;
+                    };
+                    parts[part] = value;
+                    digits += 1;
+                }
+                _ => return None,
+            }
+            i += 1;
+        }
+
+        if digits == 0 {
+            return None;
+        }
+
+        Some(Self::new(parts[0], parts[1], parts[2]))
+    }
+}
+
+impl fmt::Display for PamVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The implementation this crate was built for
+pub const IMPLEMENTATION: PamImplementation = if cfg!(pam_impl = "openpam") {
+    PamImplementation::OpenPam
+} else {
+    PamImplementation::LinuxPam
+};
+
+/// The version of the library this crate was built against, or `None` when the build could not
+/// determine it.
+///
+/// Without pkg-config, the version of Linux-PAM is taken from its newest symbol version, so it may
+/// be older than the actual release.
+pub const PAM_VERSION: Option<PamVersion> = match option_env!("LIBPAM_SYS_PAM_VERSION") {
+    Some(version) => PamVersion::parse(version),
+    None => None,
+};
+
+/// Detect the implementation of the libpam that is loaded at runtime.
+///
+/// This looks for `openpam_ttyconv`, like the build script does. With the `dlopen` feature, libpam
+/// is loaded if that has not happened yet, and `None` is returned when it cannot be loaded.
+#[cfg(any(docsrs, feature = "libc", feature = "dlopen"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "libc", feature = "dlopen"))))]
+pub fn detect() -> Option<PamImplementation> {
+    #[cfg(feature = "dlopen")]
+    let is_openpam = {
+        crate::dlopen::load().ok()?;
+        crate::dlopen::is_available("openpam_ttyconv")
+    };
+
+    #[cfg(not(feature = "dlopen"))]
+    let is_openpam = {
+        // Reference libpam, so it is not dropped from the binary when linking with `--as-needed`
+        std::hint::black_box(crate::pam_start as *const ());
+        let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, c"openpam_ttyconv".as_ptr()) };
+        !symbol.is_null()
+    };
+
+    Some(if is_openpam {
+        PamImplementation::OpenPam
+    } else {
+        PamImplementation::LinuxPam
+    })
+}
@@ -0,0 +1,279 @@
+//! Typed access to the items of a PAM handle.
+//!
+//! `pam_get_item` and `pam_set_item` take the item as a `void *`, whose type depends on the
+//! `item_type`. Every item has a marker type here that implements [`Item`], so [`get_item`] and
+//! [`set_item`] read and write the value with the type of the item. String items are an
+//! `Option<&CStr>`, and structured items have their own `#[repr(C)]` type.
+//!
+//! ```no_run
+//! use libpam_sys::item::{get_item, set_item, RHost, User};
+//! use libpam_sys::pam_handle_t;
+//!
+//! # let pamh: *mut pam_handle_t = std::ptr::null_mut();
+//! unsafe {
+//!     set_item::<RHost>(pamh, Some(c"example.com"))?;
+//!
+//!     if let Some(user) = get_item::<User>(pamh)? {
+//!         println!("Authenticating {}", user.to_string_lossy());
+//!     }
+//! }
+//! # Ok::<(), libpam_sys::error::PamError>(())
+//! ```
+
+use std::ffi::{c_int, c_void, CStr};
+use std::ptr;
+
+use crate::error::PamError;
+use crate::{pam_conv, pam_get_item, pam_handle_t, pam_set_item};
+
+/// An item of a PAM handle.
+///
+/// # Safety
+///
+/// [`Item::TYPE`] has to be an item whose value is converted correctly by [`Item::from_raw`] and
+/// [`Item::to_raw`].
+pub unsafe trait Item {
+    /// The `item_type` passed to `pam_get_item` and `pam_set_item`
+    const TYPE: c_int;
+
+    /// The typed value of the item
+    type Value<'a>;
+
+    /// Convert the pointer returned by `pam_get_item`.
+    ///
+    /// # Safety
+    ///
+    /// `item` has to be null or point to a value of the item that stays valid for `'a`.
+    unsafe fn from_raw<'a>(item: *const c_void) -> Self::Value<'a>;
+
+    /// Convert the value into the pointer passed to `pam_set_item`.
+    fn to_raw(value: Self::Value<'_>) -> *const c_void;
+}
+
+/// An item whose value libpam copies completely when it is set.
+///
+/// Setting such an item cannot leave libpam with pointers into the memory of the caller, which is
+/// what makes [`PamHandle::set_item`] safe. Items that carry pointers libpam keeps, such as
+/// [`Conv`] and [`FailDelay`], are set with [`PamHandle::set_item_unchecked`]. So is
+/// `XAuthData`, whose struct holds raw pointers and lengths that libpam copies through, unless it
+/// is set from an [`XAuth`] with [`PamHandle::set_xauth`].
+///
+/// # Safety
+///
+/// Every value of the item has to be valid for `pam_set_item`, and libpam must not keep any
+/// pointer of [`Item::to_raw`] after it returned.
+///
+/// [`PamHandle::set_item`]: crate::handle::PamHandle::set_item
+/// [`PamHandle::set_item_unchecked`]: crate::handle::PamHandle::set_item_unchecked
+/// [`PamHandle::set_xauth`]: crate::handle::PamHandle::set_xauth
+/// [`XAuth`]: crate::xauth::XAuth
+pub unsafe trait CopiedItem: Item {}
+
+/// Read the item `I` with `pam_get_item`.
+///
+/// Returns the error of `pam_get_item` when it fails.
+///
+/// # Safety
+///
+/// `pamh` has to be a valid handle. The value is owned by libpam and is only valid until the item
+/// is set again or the handle is ended.
+pub unsafe fn get_item<'a, I: Item>(pamh: *const pam_handle_t) -> Result<I::Value<'a>, PamError> {
+    let mut item: *const c_void = ptr::null();
+    PamError::check(pam_get_item(pamh, I::TYPE, &mut item))?;
+
+    Ok(I::from_raw(item))
+}
+
+/// Set the item `I` with `pam_set_item`, which stores a copy of the value.
+///
+/// Returns the error of `pam_set_item` when it fails.
+///
+/// # Safety
+///
+/// `pamh` has to be a valid handle.
+pub unsafe fn set_item<I: Item>(
+    pamh: *mut pam_handle_t,
+    value: I::Value<'_>,
+) -> Result<(), PamError> {
+    PamError::check(pam_set_item(pamh, I::TYPE, I::to_raw(value)))
+}
+
+/// Implements [`Item`] for marker types of items that are strings.
+///
+/// The docs only go on the marker types, and the impls only get their `cfg`.
+macro_rules! string_items {
+    ($(
+        $(#[doc = $doc:expr])*
+        $(#[cfg($cfg:meta)])?
+        $(#[cfg_attr($($cfg_attr:tt)*)])*
+        $name:ident = $item_type:path;
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            $(#[cfg_attr($($cfg_attr)*)])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            $(#[cfg($cfg)])?
+            unsafe impl CopiedItem for $name {}
+
+            $(#[cfg($cfg)])?
+            unsafe impl Item for $name {
+                const TYPE: c_int = $item_type;
+                type Value<'a> = Option<&'a CStr>;
+
+                unsafe fn from_raw<'a>(item: *const c_void) -> Self::Value<'a> {
+                    (!item.is_null()).then(|| CStr::from_ptr(item.cast()))
+                }
+
+                fn to_raw(value: Self::Value<'_>) -> *const c_void {
+                    value.map_or(ptr::null(), |value| value.as_ptr().cast())
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`Item`] for marker types of items that are structs.
+macro_rules! struct_items {
+    ($(
+        $(#[doc = $doc:expr])*
+        $(#[cfg($cfg:meta)])?
+        $(#[cfg_attr($($cfg_attr:tt)*)])*
+        $name:ident = $item_type:path => $ty:ty;
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            $(#[cfg($cfg)])?
+            $(#[cfg_attr($($cfg_attr)*)])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+
+            $(#[cfg($cfg)])?
+            unsafe impl Item for $name {
+                const TYPE: c_int = $item_type;
+                type Value<'a> = Option<&'a $ty>;
+
+                unsafe fn from_raw<'a>(item: *const c_void) -> Self::Value<'a> {
+                    item.cast::<$ty>().as_ref()
+                }
+
+                fn to_raw(value: Self::Value<'_>) -> *const c_void {
+                    value.map_or(ptr::null(), |value| (value as *const $ty).cast())
+                }
+            }
+        )*
+    };
+}
+
+/// `PAM_SERVICE`, the name of the service
+///
+/// libpam sets it in `pam_start`, and it cannot be unset. Linux-PAM lowercases the name in place
+/// without checking for null, so the value is a `&CStr` instead of an `Option`.
+///
+/// ```compile_fail
+/// # use libpam_sys::handle::PamHandle;
+/// # use libpam_sys::item::Service;
+/// # fn set(pamh: &mut PamHandle) {
+/// pamh.set_item::<Service>(None);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Service;
+
+unsafe impl CopiedItem for Service {}
+
+unsafe impl Item for Service {
+    const TYPE: c_int = crate::PAM_SERVICE;
+    type Value<'a> = &'a CStr;
+
+    unsafe fn from_raw<'a>(item: *const c_void) -> Self::Value<'a> {
+        // Only a handle that failed to start has no service
+        if item.is_null() {
+            c""
+        } else {
+            CStr::from_ptr(item.cast())
+        }
+    }
+
+    fn to_raw(value: Self::Value<'_>) -> *const c_void {
+        value.as_ptr().cast()
+    }
+}
+
+string_items! {
+    /// `PAM_USER`, the name of the user
+    User = crate::PAM_USER;
+    /// `PAM_TTY`, the terminal of the user
+    Tty = crate::PAM_TTY;
+    /// `PAM_RHOST`, the remote host of the user
+    RHost = crate::PAM_RHOST;
+    /// `PAM_AUTHTOK`, the authentication token. Only available to modules.
+    AuthTok = crate::PAM_AUTHTOK;
+    /// `PAM_OLDAUTHTOK`, the old authentication token. Only available to modules.
+    OldAuthTok = crate::PAM_OLDAUTHTOK;
+    /// `PAM_RUSER`, the name of the remote user
+    RUser = crate::PAM_RUSER;
+    /// `PAM_USER_PROMPT`, the prompt used by `pam_get_user`
+    UserPrompt = crate::PAM_USER_PROMPT;
+
+    /// `PAM_XDISPLAY`, the name of the X display
+    #[cfg(any(docsrs, pam_impl = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+    XDisplay = crate::linux_pam::PAM_XDISPLAY;
+    /// `PAM_AUTHTOK_TYPE`, the type of token in the prompts of `pam_get_authtok`
+    #[cfg(any(docsrs, pam_impl = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+    AuthTokType = crate::linux_pam::PAM_AUTHTOK_TYPE;
+
+    /// `PAM_AUTHTOK_PROMPT`, the prompt used by `pam_get_authtok`
+    #[cfg(any(docsrs, pam_impl = "openpam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "openpam")))]
+    AuthTokPrompt = crate::openpam::PAM_AUTHTOK_PROMPT;
+    /// `PAM_OLDAUTHTOK_PROMPT`, the prompt for the old token used by `pam_get_authtok`
+    #[cfg(any(docsrs, pam_impl = "openpam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "openpam")))]
+    OldAuthTokPrompt = crate::openpam::PAM_OLDAUTHTOK_PROMPT;
+    /// `PAM_HOST`, the name of the host the application runs on
+    #[cfg(any(docsrs, pam_impl = "openpam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "openpam")))]
+    Host = crate::openpam::PAM_HOST;
+}
+
+struct_items! {
+    /// `PAM_CONV`, the conversation of the application
+    Conv = crate::PAM_CONV => pam_conv;
+
+    /// `PAM_XAUTHDATA`, the authentication data for the X display
+    #[cfg(any(docsrs, pam_impl = "linux-pam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+    XAuthData = crate::linux_pam::PAM_XAUTHDATA => crate::linux_pam::pam_xauth_data;
+
+    /// `PAM_REPOSITORY`, the user database. libpam copies the struct, but not the data it points
+    /// to.
+    #[cfg(any(docsrs, pam_impl = "openpam"))]
+    #[cfg_attr(docsrs, doc(cfg(pam_impl = "openpam")))]
+    Repository = crate::openpam::PAM_REPOSITORY => crate::openpam::pam_repository;
+}
+
+/// `PAM_FAIL_DELAY`, the function that replaces the delay after a failed authentication
+#[cfg(any(docsrs, pam_impl = "linux-pam"))]
+#[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+#[derive(Debug, Clone, Copy)]
+pub struct FailDelay;
+
+#[cfg(any(docsrs, pam_impl = "linux-pam"))]
+unsafe impl Item for FailDelay {
+    const TYPE: c_int = crate::linux_pam::PAM_FAIL_DELAY;
+    type Value<'a> = crate::linux_pam::pam_fail_delay_fn;
+
+    unsafe fn from_raw<'a>(item: *const c_void) -> Self::Value<'a> {
+        // The item is the function pointer itself
+        std::mem::transmute::<*const c_void, Self::Value<'a>>(item)
+    }
+
+    fn to_raw(value: Self::Value<'_>) -> *const c_void {
+        value.map_or(ptr::null(), |function| function as *const c_void)
+    }
+}
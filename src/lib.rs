@@ -7,8 +7,310 @@ compile_error!("Cannot support two implementations of PAM at the same time. Cons
 #[cfg(not(any(pam_impl = "linux-pam", pam_impl = "openpam")))]
 compile_error!("No PAM implementation is specified.");
 
+#[cfg(all(feature = "dlopen", feature = "vendored"))]
+compile_error!("The `dlopen` and `vendored` features cannot be combined.");
+
 use std::ffi::{c_char, c_int, c_void};
 
+/// Declare functions and statics of libpam.
+///
+/// This takes an `extern "C"` block. Normally, that block is emitted as is. With the `dlopen`
+/// feature, every item is turned into a wrapper that resolves the symbol at runtime. The symbols
+/// are resolved in libpam, unless the block is written as `extern "C" in LIBRARY { ... }` with one
+/// of the libraries of the `dlopen` module.
+macro_rules! pam_extern {
+    (
+        $(#[$block_attr:meta])*
+        extern "C" {
+            $($items:tt)*
+        }
+    ) => {
+        pam_extern!(@items [$(#[$block_attr])*] [LIBPAM] $($items)*);
+    };
+
+    (
+        $(#[$block_attr:meta])*
+        extern "C" in $library:ident {
+            $($items:tt)*
+        }
+    ) => {
+        pam_extern!(@items [$(#[$block_attr])*] [$library] $($items)*);
+    };
+
+    (@items [$($block_attr:tt)*] [$library:ident]) => {};
+
+    // Variadic functions
+    (
+        @items [$($block_attr:tt)*] [$library:ident]
+        $(#[$attr:meta])*
+        pub fn $name:ident($($arg:ident: $argty:ty,)+ ...) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[cfg(not(feature = "dlopen"))]
+        $($block_attr)*
+        extern "C" {
+            $(#[$attr])*
+            pub fn $name($($arg: $argty,)+ ...) $(-> $ret)?;
+        }
+
+        #[cfg(feature = "dlopen")]
+        $($block_attr)*
+        $(#[$attr])*
+        ///
+        /// # Panics
+        ///
+        /// With the `dlopen` feature, this panics when the symbol cannot be resolved.
+        #[allow(non_upper_case_globals)]
+        pub static $name: $crate::dlopen::Symbol<
+            unsafe extern "C" fn($($argty,)+ ...) $(-> $ret)?
+        > = $crate::dlopen::Symbol::new(
+            &$crate::dlopen::$library,
+            concat!(stringify!($name), "\0"),
+        );
+
+        pam_extern!(@items [$($block_attr)*] [$library] $($rest)*);
+    };
+
+    // Functions that were added in a later release, written with `#[since(cfg)]` first. They are
+    // linked when the library is at least that release. Otherwise they are resolved at runtime,
+    // and return `PAM_SYMBOL_ERR` or a null pointer when the library does not have them.
+    (
+        @items [$($block_attr:tt)*] [$library:ident]
+        #[since($since:ident)]
+        $(#[$attr:meta])*
+        pub fn $name:ident($($arg:ident: $argty:ty),* $(,)?) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        pam_extern!(
+            @items [$($block_attr)* #[cfg(any($since, feature = "dlopen"))]] [$library]
+            $(#[$attr])*
+            pub fn $name($($arg: $argty),*) $(-> $ret)?;
+        );
+
+        #[cfg(not(any($since, feature = "dlopen")))]
+        $($block_attr)*
+        $(#[$attr])*
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub unsafe fn $name($($arg: $argty),*) $(-> $ret)? {
+            static SYMBOL: $crate::optional::OptionalSymbol<
+                unsafe extern "C" fn($($argty),*) $(-> $ret)?
+            > = $crate::optional::OptionalSymbol::new(concat!(stringify!($name), "\0"));
+
+            match SYMBOL.get() {
+                Some(function) => function($($arg),*),
+                None => $crate::optional::Missing::missing(),
+            }
+        }
+
+        pam_extern!(@items [$($block_attr)*] [$library] $($rest)*);
+    };
+
+    // Functions
+    (
+        @items [$($block_attr:tt)*] [$library:ident]
+        $(#[$attr:meta])*
+        pub fn $name:ident($($arg:ident: $argty:ty),* $(,)?) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        #[cfg(not(feature = "dlopen"))]
+        $($block_attr)*
+        extern "C" {
+            $(#[$attr])*
+            pub fn $name($($arg: $argty),*) $(-> $ret)?;
+        }
+
+        #[cfg(feature = "dlopen")]
+        $($block_attr)*
+        $(#[$attr])*
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub unsafe fn $name($($arg: $argty),*) $(-> $ret)? {
+            static SYMBOL: $crate::dlopen::Symbol<unsafe extern "C" fn($($argty),*) $(-> $ret)?> =
+                $crate::dlopen::Symbol::new(
+                    &$crate::dlopen::$library,
+                    concat!(stringify!($name), "\0"),
+                );
+
+            match SYMBOL.get() {
+                Ok(function) => function($($arg),*),
+                Err(err) => $crate::dlopen::MissingSymbol::missing(err),
+            }
+        }
+
+        pam_extern!(@items [$($block_attr)*] [$library] $($rest)*);
+    };
+
+    // Mutable statics
+    (
+        @items [$($block_attr:tt)*] [$library:ident]
+        $(#[$attr:meta])*
+        pub static mut $name:ident: $ty:ty;
+        $($rest:tt)*
+    ) => {
+        #[cfg(not(feature = "dlopen"))]
+        $($block_attr)*
+        extern "C" {
+            $(#[$attr])*
+            pub static mut $name: $ty;
+        }
+
+        #[cfg(feature = "dlopen")]
+        $($block_attr)*
+        $(#[$attr])*
+        ///
+        /// # Panics
+        ///
+        /// With the `dlopen` feature, this panics when the symbol cannot be resolved.
+        #[allow(non_upper_case_globals)]
+        pub static $name: $crate::dlopen::StaticMut<$ty> = $crate::dlopen::StaticMut::new(
+            &$crate::dlopen::$library,
+            concat!(stringify!($name), "\0"),
+        );
+
+        pam_extern!(@items [$($block_attr)*] [$library] $($rest)*);
+    };
+
+    // Statics
+    (
+        @items [$($block_attr:tt)*] [$library:ident]
+        $(#[$attr:meta])*
+        pub static $name:ident: $ty:ty;
+        $($rest:tt)*
+    ) => {
+        #[cfg(not(feature = "dlopen"))]
+        $($block_attr)*
+        extern "C" {
+            $(#[$attr])*
+            pub static $name: $ty;
+        }
+
+        #[cfg(feature = "dlopen")]
+        $($block_attr)*
+        $(#[$attr])*
+        ///
+        /// # Panics
+        ///
+        /// With the `dlopen` feature, this panics when the symbol cannot be resolved.
+        #[allow(non_upper_case_globals)]
+        pub static $name: $crate::dlopen::Static<$ty> = $crate::dlopen::Static::new(
+            &$crate::dlopen::$library,
+            concat!(stringify!($name), "\0"),
+        );
+
+        pam_extern!(@items [$($block_attr)*] [$library] $($rest)*);
+    };
+}
+
+/// A pointer to a mutable static of libpam, such as `pam_misc_conv_warn_time`.
+///
+/// The static is a `static mut` when libpam is linked, but a wrapper with the `dlopen` feature.
+/// This macro returns a `*mut` pointer to it in both cases.
+///
+/// # Panics
+///
+/// With the `dlopen` feature, this panics when the symbol cannot be resolved.
+#[cfg(not(feature = "dlopen"))]
+#[macro_export]
+macro_rules! static_mut_ptr {
+    ($static:path) => {
+        ::std::ptr::addr_of_mut!($static)
+    };
+}
+
+/// A pointer to a mutable static of libpam, such as `pam_misc_conv_warn_time`.
+///
+/// The static is a `static mut` when libpam is linked, but a wrapper with the `dlopen` feature.
+/// This macro returns a `*mut` pointer to it in both cases.
+///
+/// # Panics
+///
+/// With the `dlopen` feature, this panics when the symbol cannot be resolved.
+#[cfg(feature = "dlopen")]
+#[macro_export]
+macro_rules! static_mut_ptr {
+    ($static:path) => {
+        $static.as_ptr()
+    };
+}
+
+/// Define `to_status` and `from_status` for the status codes of an implementation.
+///
+/// Every row pairs a status code constant of the module with its [`status::PamStatus`].
+macro_rules! status_codes {
+    ($($code:ident => $status:ident,)*) => {
+        /// The implementation-neutral status of `code`, or `None` when this implementation does
+        /// not define `code`
+        pub const fn to_status(code: c_int) -> Option<$crate::status::PamStatus> {
+            match code {
+                $($code => Some($crate::status::PamStatus::$status),)*
+                _ => None,
+            }
+        }
+
+        /// The status code of `status` in this implementation, or `None` when the status only
+        /// exists in the other implementation
+        pub const fn from_status(status: $crate::status::PamStatus) -> Option<c_int> {
+            match status {
+                $($crate::status::PamStatus::$status => Some($code),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+#[cfg(any(docsrs, feature = "async"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod asynchronous;
+#[cfg(any(docsrs, feature = "conv"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "conv")))]
+pub mod conv;
+#[cfg(any(docsrs, feature = "dlopen"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "dlopen")))]
+pub mod dlopen;
+pub mod error;
+#[cfg(any(docsrs, feature = "flags"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "flags")))]
+pub mod flags;
+#[cfg(any(docsrs, feature = "handle"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "handle")))]
+pub mod handle;
+pub mod implementation;
+pub mod item;
+pub mod message;
+pub mod module;
+// The newest `#[since(...)]` cfg is only missing when the library is older than some function.
+// The unit tests resolve missing symbols with any library.
+#[cfg(all(
+    feature = "linux-pam",
+    not(feature = "dlopen"),
+    any(test, not(pam_linux_1_4_1))
+))]
+mod optional;
+#[cfg(any(docsrs, feature = "pamc"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "pamc")))]
+pub mod pamc;
+#[cfg(any(docsrs, feature = "libc"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "libc")))]
+pub mod response;
+#[cfg(any(docsrs, feature = "resume"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "resume")))]
+pub mod resume;
+pub mod status;
+#[cfg(any(docsrs, feature = "libc", pam_impl = "linux-pam"))]
+mod wipe;
+#[cfg(any(docsrs, pam_impl = "linux-pam"))]
+#[cfg_attr(docsrs, doc(cfg(pam_impl = "linux-pam")))]
+pub mod xauth;
+
+pub use implementation::{IMPLEMENTATION, PAM_VERSION};
+
+/// A `va_list` as it is passed to a function.
+///
+/// On all supported platforms, a `va_list` argument is passed as a single pointer. A `va_list`
+/// received from C can be passed on as is.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+pub struct va_list(pub *mut c_void);
+
 #[repr(C)]
 pub struct pam_handle_t {
     // Structure should never actually be instantiated. Only the alignment is important
@@ -18,7 +320,7 @@ pub struct pam_handle_t {
 #[repr(C)]
 pub struct pam_conv {
     pub conv:
-        extern "C" fn(c_int, *const *mut pam_message, *mut *mut pam_response, *mut c_void) -> c_int,
+        extern "C" fn(c_int, *mut *const pam_message, *mut *mut pam_response, *mut c_void) -> c_int,
     pub app_dataptr: *mut c_void,
 }
 
@@ -31,99 +333,80 @@ pub struct pam_response {
 #[repr(C)]
 pub struct pam_message {
     pub msg_style: c_int,
+    #[cfg(pam_impl = "linux-pam")]
     pub msg: *const c_char,
+    /// OpenPAM declares the message as `char *`, although it is never written through.
+    #[cfg(pam_impl = "openpam")]
+    pub msg: *mut c_char,
 }
 
 // Application functions
-extern "C" {
-    pub fn pam_start(
-        service_name: *const c_char,
-        user: *const c_char,
-        pam_conversation: *const pam_conv,
-        pamh: *mut *mut pam_handle_t,
-    ) -> c_int;
+pam_extern! {
+    extern "C" {
+        pub fn pam_start(
+            service_name: *const c_char,
+            user: *const c_char,
+            pam_conversation: *const pam_conv,
+            pamh: *mut *mut pam_handle_t,
+        ) -> c_int;
 
-    pub fn pam_end(pamh: *mut pam_handle_t, pam_status: c_int) -> c_int;
+        pub fn pam_end(pamh: *mut pam_handle_t, pam_status: c_int) -> c_int;
 
-    pub fn pam_authenticate(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
-    pub fn pam_setcred(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
-    pub fn pam_acct_mgmt(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_authenticate(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_setcred(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_acct_mgmt(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
 
-    pub fn pam_open_session(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
-    pub fn pam_close_session(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_open_session(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_close_session(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
 
-    pub fn pam_chauthtok(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+        pub fn pam_chauthtok(pamh: *mut pam_handle_t, flags: c_int) -> c_int;
+    }
 }
 
 // General Functions
-extern "C" {
-    pub fn pam_strerror(pamh: *mut pam_handle_t, errnum: c_int) -> *const c_char;
-
-    pub fn pam_set_item(pamh: *mut pam_handle_t, item_type: c_int, item: *const c_void) -> c_int;
-    pub fn pam_get_item(pamh: *const pam_handle_t, item_type: c_int, item: *const *mut c_void);
-
-    pub fn pam_getenv(pamh: *mut pam_handle_t, name: *const c_char) -> *const c_char;
-    pub fn pam_putenv(pamh: *mut pam_handle_t, name_value: *const c_char) -> c_int;
-    pub fn pam_getenvlist(pamh: *mut pam_handle_t) -> *mut *const c_char;
-
-    pub fn pam_get_user(
-        pamh: *mut pam_handle_t,
-        user: *const *mut c_char,
-        prompt: *const c_char,
-    ) -> c_int;
-
-    pub fn pam_get_data(
-        pamh: *mut pam_handle_t,
-        module_data_name: *const c_char,
-        data: *const *mut c_void,
-    ) -> c_int;
-    pub fn pam_set_data(
-        pamh: *mut pam_handle_t,
-        module_data_name: *const c_char,
-        data: *mut c_void,
-        cleanup: Option<extern "C" fn(*mut pam_handle_t, *mut c_void, c_int)>,
-    ) -> c_int;
+pam_extern! {
+    extern "C" {
+        #[cfg(pam_impl = "linux-pam")]
+        pub fn pam_strerror(pamh: *mut pam_handle_t, errnum: c_int) -> *const c_char;
+        /// OpenPAM takes a `const` handle.
+        #[cfg(pam_impl = "openpam")]
+        pub fn pam_strerror(pamh: *const pam_handle_t, errnum: c_int) -> *const c_char;
+
+        pub fn pam_set_item(pamh: *mut pam_handle_t, item_type: c_int, item: *const c_void) -> c_int;
+        pub fn pam_get_item(
+            pamh: *const pam_handle_t,
+            item_type: c_int,
+            item: *mut *const c_void,
+        ) -> c_int;
+
+        pub fn pam_getenv(pamh: *mut pam_handle_t, name: *const c_char) -> *const c_char;
+        pub fn pam_putenv(pamh: *mut pam_handle_t, name_value: *const c_char) -> c_int;
+        pub fn pam_getenvlist(pamh: *mut pam_handle_t) -> *mut *mut c_char;
+
+        pub fn pam_get_user(
+            pamh: *mut pam_handle_t,
+            user: *mut *const c_char,
+            prompt: *const c_char,
+        ) -> c_int;
+
+        pub fn pam_get_data(
+            pamh: *const pam_handle_t,
+            module_data_name: *const c_char,
+            data: *mut *const c_void,
+        ) -> c_int;
+        pub fn pam_set_data(
+            pamh: *mut pam_handle_t,
+            module_data_name: *const c_char,
+            data: *mut c_void,
+            cleanup: Option<extern "C" fn(*mut pam_handle_t, *mut c_void, c_int)>,
+        ) -> c_int;
+    }
 }
 
 // Module functions
-extern "C" {
-    pub fn pam_sm_acct_mgmt(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-    pub fn pam_sm_authenticate(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-    pub fn pam_sm_chauthtok(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-    pub fn pam_sm_open_session(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-    pub fn pam_sm_close_session(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-    pub fn pam_sm_setcred(
-        pamh: *mut pam_handle_t,
-        flags: c_int,
-        argc: c_int,
-        argv: *const *mut c_char,
-    ) -> c_int;
-}
+//
+// The `pam_sm_*` functions are defined by service modules rather than by libpam. They are exported
+// with the `pam_module!` macro.
 
 macro_rules! reexport_based_on_features {
     (
@@ -291,6 +574,12 @@ reexport_based_on_features! {
     /// Extend lifetime of user credentials
     PAM_REFRESH_CRED,
 
+    /// The password service should only perform preliminary checks. No passwords should be updated.
+    PAM_PRELIM_CHECK,
+
+    /// The password service should update passwords.
+    PAM_UPDATE_AUTHTOK,
+
     /// The password service should only update those passwords that have aged.  If this flag is not
     /// passed, the password service should update all passwords.
     PAM_CHANGE_EXPIRED_AUTHTOK,
@@ -331,20 +620,389 @@ pub mod linux_pam {
 
     #[cfg(any(docsrs, feature = "linux-pam"))]
     use {
-        super::{pam_conv, pam_handle_t},
-        std::ffi::c_char,
+        super::{pam_conv, pam_handle_t, va_list},
+        std::ffi::{c_char, c_uint},
     };
 
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "linux-pam"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
+        extern "C" {
+            #[since(pam_linux_1_4)]
+            pub fn pam_start_confdir(
+                service_name: *const c_char,
+                user: *const c_char,
+                pam_conversation: *const pam_conv,
+                conf_dir: *const c_char,
+                pamh: *mut *mut pam_handle_t,
+            ) -> c_int;
+
+            pub fn pam_fail_delay(pamh: *mut pam_handle_t, musec_delay: c_uint) -> c_int;
+
+            // Extensions from `pam_ext.h`
+            pub fn pam_vsyslog(
+                pamh: *const pam_handle_t,
+                priority: c_int,
+                fmt: *const c_char,
+                args: va_list,
+            );
+
+            pub fn pam_syslog(
+                pamh: *const pam_handle_t,
+                priority: c_int,
+                fmt: *const c_char,
+                ...
+            );
+
+            pub fn pam_vprompt(
+                pamh: *mut pam_handle_t,
+                style: c_int,
+                response: *mut *mut c_char,
+                fmt: *const c_char,
+                args: va_list,
+            ) -> c_int;
+
+            pub fn pam_prompt(
+                pamh: *mut pam_handle_t,
+                style: c_int,
+                response: *mut *mut c_char,
+                fmt: *const c_char,
+                ...
+            ) -> c_int;
+
+            #[since(pam_linux_1_1)]
+            pub fn pam_get_authtok(
+                pamh: *mut pam_handle_t,
+                item: c_int,
+                authtok: *mut *const c_char,
+                prompt: *const c_char,
+            ) -> c_int;
+
+            #[since(pam_linux_1_1_1)]
+            pub fn pam_get_authtok_noverify(
+                pamh: *mut pam_handle_t,
+                authtok: *mut *const c_char,
+                prompt: *const c_char,
+            ) -> c_int;
+
+            #[since(pam_linux_1_1_1)]
+            pub fn pam_get_authtok_verify(
+                pamh: *mut pam_handle_t,
+                authtok: *mut *const c_char,
+                prompt: *const c_char,
+            ) -> c_int;
+        }
+    }
+
+    // Linux-PAM defines `pam_error`, `pam_verror`, `pam_info` and `pam_vinfo` as C macros around
+    // `pam_prompt` and `pam_vprompt`, so there are no symbols to bind to. These are provided as Rust
+    // macros with the same arguments instead.
+
     #[cfg(any(docsrs, feature = "linux-pam"))]
     #[cfg_attr(docsrs, doc(cfg(feature = "linux-pam")))]
-    extern "C" {
-        pub fn pam_start_confdir(
-            service_name: *const c_char,
-            user: *const c_char,
-            pam_conversation: *const pam_conv,
-            conf_dir: *const c_char,
-            pamh: *mut *mut pam_handle_t,
-        ) -> c_int;
+    pub use crate::{
+        __linux_pam_error as pam_error, __linux_pam_info as pam_info,
+        __linux_pam_verror as pam_verror, __linux_pam_vinfo as pam_vinfo,
+    };
+
+    /// Display an error message, e.g. `pam_error!(pamh, fmt, args...)`.
+    ///
+    /// This is `pam_prompt(pamh, PAM_ERROR_MSG, NULL, fmt, args...)`.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __linux_pam_error {
+        ($pamh:expr, $fmt:expr $(, $arg:expr)* $(,)?) => {
+            $crate::linux_pam::pam_prompt(
+                $pamh,
+                $crate::linux_pam::PAM_ERROR_MSG,
+                ::std::ptr::null_mut(),
+                $fmt
+                $(, $arg)*
+            )
+        };
+    }
+
+    /// Display an error message from a `va_list`, e.g. `pam_verror!(pamh, fmt, args)`.
+    ///
+    /// This is `pam_vprompt(pamh, PAM_ERROR_MSG, NULL, fmt, args)`.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __linux_pam_verror {
+        ($pamh:expr, $fmt:expr, $args:expr $(,)?) => {
+            $crate::linux_pam::pam_vprompt(
+                $pamh,
+                $crate::linux_pam::PAM_ERROR_MSG,
+                ::std::ptr::null_mut(),
+                $fmt,
+                $args,
+            )
+        };
+    }
+
+    /// Display an informational message, e.g. `pam_info!(pamh, fmt, args...)`.
+    ///
+    /// This is `pam_prompt(pamh, PAM_TEXT_INFO, NULL, fmt, args...)`.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __linux_pam_info {
+        ($pamh:expr, $fmt:expr $(, $arg:expr)* $(,)?) => {
+            $crate::linux_pam::pam_prompt(
+                $pamh,
+                $crate::linux_pam::PAM_TEXT_INFO,
+                ::std::ptr::null_mut(),
+                $fmt
+                $(, $arg)*
+            )
+        };
+    }
+
+    /// Display an informational message from a `va_list`, e.g. `pam_vinfo!(pamh, fmt, args)`.
+    ///
+    /// This is `pam_vprompt(pamh, PAM_TEXT_INFO, NULL, fmt, args)`.
+    #[cfg(any(docsrs, feature = "linux-pam"))]
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __linux_pam_vinfo {
+        ($pamh:expr, $fmt:expr, $args:expr $(,)?) => {
+            $crate::linux_pam::pam_vprompt(
+                $pamh,
+                $crate::linux_pam::PAM_TEXT_INFO,
+                ::std::ptr::null_mut(),
+                $fmt,
+                $args,
+            )
+        };
+    }
+
+    // Helper functions from `pam_modutil.h`, for use in service modules
+    #[cfg(any(docsrs, feature = "modutil"))]
+    use libc::{gid_t, group, passwd, spwd, uid_t};
+
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "modutil"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "modutil")))]
+        extern "C" {
+            #[since(pam_linux_1_4_1)]
+            pub fn pam_modutil_check_user_in_passwd(
+                pamh: *mut pam_handle_t,
+                user_name: *const c_char,
+                file_name: *const c_char,
+            ) -> c_int;
+
+            pub fn pam_modutil_getpwnam(
+                pamh: *mut pam_handle_t,
+                user: *const c_char,
+            ) -> *mut passwd;
+
+            pub fn pam_modutil_getpwuid(pamh: *mut pam_handle_t, uid: uid_t) -> *mut passwd;
+
+            pub fn pam_modutil_getgrnam(
+                pamh: *mut pam_handle_t,
+                group: *const c_char,
+            ) -> *mut group;
+
+            pub fn pam_modutil_getgrgid(pamh: *mut pam_handle_t, gid: gid_t) -> *mut group;
+
+            pub fn pam_modutil_getspnam(pamh: *mut pam_handle_t, user: *const c_char) -> *mut spwd;
+
+            pub fn pam_modutil_user_in_group_nam_nam(
+                pamh: *mut pam_handle_t,
+                user: *const c_char,
+                group: *const c_char,
+            ) -> c_int;
+
+            pub fn pam_modutil_user_in_group_nam_gid(
+                pamh: *mut pam_handle_t,
+                user: *const c_char,
+                group: gid_t,
+            ) -> c_int;
+
+            pub fn pam_modutil_user_in_group_uid_nam(
+                pamh: *mut pam_handle_t,
+                user: uid_t,
+                group: *const c_char,
+            ) -> c_int;
+
+            pub fn pam_modutil_user_in_group_uid_gid(
+                pamh: *mut pam_handle_t,
+                user: uid_t,
+                group: gid_t,
+            ) -> c_int;
+
+            pub fn pam_modutil_getlogin(pamh: *mut pam_handle_t) -> *const c_char;
+
+            pub fn pam_modutil_read(fd: c_int, buffer: *mut c_char, count: c_int) -> c_int;
+
+            pub fn pam_modutil_write(fd: c_int, buffer: *const c_char, count: c_int) -> c_int;
+
+            #[since(pam_linux_1_1)]
+            pub fn pam_modutil_audit_write(
+                pamh: *mut pam_handle_t,
+                type_: c_int,
+                message: *const c_char,
+                retval: c_int,
+            ) -> c_int;
+
+            #[since(pam_linux_1_1_3)]
+            pub fn pam_modutil_drop_priv(
+                pamh: *mut pam_handle_t,
+                p: *mut pam_modutil_privs,
+                pw: *const passwd,
+            ) -> c_int;
+
+            #[since(pam_linux_1_1_3)]
+            pub fn pam_modutil_regain_priv(
+                pamh: *mut pam_handle_t,
+                p: *mut pam_modutil_privs,
+            ) -> c_int;
+
+            #[since(pam_linux_1_1_9)]
+            pub fn pam_modutil_sanitize_helper_fds(
+                pamh: *mut pam_handle_t,
+                redirect_stdin: pam_modutil_redirect_fd,
+                redirect_stdout: pam_modutil_redirect_fd,
+                redirect_stderr: pam_modutil_redirect_fd,
+            ) -> c_int;
+
+            #[since(pam_linux_1_3_2)]
+            pub fn pam_modutil_search_key(
+                pamh: *mut pam_handle_t,
+                file_name: *const c_char,
+                key: *const c_char,
+            ) -> *mut c_char;
+        }
+    }
+
+    #[cfg(any(docsrs, feature = "modutil"))]
+    mod modutil {
+        use std::ffi::{c_int, c_uint};
+
+        use libc::{gid_t, uid_t};
+
+        /// The number of entries in the group list created by `PAM_MODUTIL_DEF_PRIVS`
+        pub const PAM_MODUTIL_NGROUPS: c_int = 64;
+
+        /// The saved credentials for [`pam_modutil_drop_priv`] and [`pam_modutil_regain_priv`].
+        ///
+        /// The C macro `PAM_MODUTIL_DEF_PRIVS` has no Rust equivalent. It initializes `grplist`
+        /// with an array of [`PAM_MODUTIL_NGROUPS`] elements, `number_of_groups` with the length
+        /// of that array, `allocated` and `is_dropped` with `0`, and `old_gid` and `old_uid` with
+        /// `-1`.
+        ///
+        /// [`pam_modutil_drop_priv`]: super::pam_modutil_drop_priv
+        /// [`pam_modutil_regain_priv`]: super::pam_modutil_regain_priv
+        #[repr(C)]
+        #[derive(Debug)]
+        pub struct pam_modutil_privs {
+            pub grplist: *mut gid_t,
+            pub number_of_groups: c_int,
+            pub allocated: c_int,
+            pub old_gid: gid_t,
+            pub old_uid: uid_t,
+            pub is_dropped: c_int,
+        }
+
+        /// `enum pam_modutil_redirect_fd`
+        pub type pam_modutil_redirect_fd = c_uint;
+        pub const PAM_MODUTIL_IGNORE_FD: pam_modutil_redirect_fd = 0;
+        pub const PAM_MODUTIL_PIPE_FD: pam_modutil_redirect_fd = 1;
+        pub const PAM_MODUTIL_NULL_FD: pam_modutil_redirect_fd = 2;
+    }
+
+    #[cfg(any(docsrs, feature = "modutil"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "modutil")))]
+    pub use modutil::*;
+
+    // Functions and variables from `pam_misc.h`, which are part of libpam_misc
+    #[cfg(any(docsrs, feature = "misc"))]
+    use {
+        super::{pam_message, pam_response},
+        libc::time_t,
+        std::ffi::c_void,
+    };
+
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "misc"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "misc")))]
+        extern "C" in LIBPAM_MISC {
+            /// A conversation function for text based applications that reads from and writes to
+            /// the terminal.
+            pub fn misc_conv(
+                num_msg: c_int,
+                msgm: *mut *const pam_message,
+                response: *mut *mut pam_response,
+                appdata_ptr: *mut c_void,
+            ) -> c_int;
+
+            /// The time at which [`misc_conv`] prints [`pam_misc_conv_warn_line`], or `0`
+            pub static mut pam_misc_conv_warn_time: time_t;
+            /// The time at which [`misc_conv`] prints [`pam_misc_conv_die_line`] and gives up,
+            /// or `0`
+            pub static mut pam_misc_conv_die_time: time_t;
+            /// Set when [`misc_conv`] gave up because [`pam_misc_conv_die_time`] was reached
+            pub static mut pam_misc_conv_died: c_int;
+            pub static mut pam_misc_conv_warn_line: *const c_char;
+            pub static mut pam_misc_conv_die_line: *const c_char;
+
+            /// Handles [`PAM_BINARY_PROMPT`] messages in [`misc_conv`]
+            pub static mut pam_binary_handler_fn:
+                Option<unsafe extern "C" fn(*mut c_void, *mut pamc_bp_t) -> c_int>;
+            /// Frees the prompts returned by [`pam_binary_handler_fn`]
+            pub static mut pam_binary_handler_free:
+                Option<unsafe extern "C" fn(*mut c_void, pamc_bp_t)>;
+
+            pub fn pam_misc_paste_env(
+                pamh: *mut pam_handle_t,
+                user_env: *const *const c_char,
+            ) -> c_int;
+
+            pub fn pam_misc_drop_env(env: *mut *mut c_char) -> *mut *mut c_char;
+
+            pub fn pam_misc_setenv(
+                pamh: *mut pam_handle_t,
+                name: *const c_char,
+                value: *const c_char,
+                readonly: c_int,
+            ) -> c_int;
+        }
+    }
+
+    // Agent support from `pam_client.h`, which is part of libpamc
+    #[cfg(any(docsrs, feature = "pamc"))]
+    mod pamc {
+        /// The opaque state of libpamc
+        #[repr(C)]
+        pub struct pamc_handle_s {
+            _private: [u8; 0],
+        }
+
+        /// A handle to libpamc, which is created by [`pamc_start`](super::pamc_start)
+        pub type pamc_handle_t = *mut pamc_handle_s;
+    }
+
+    #[cfg(any(docsrs, feature = "pamc"))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pamc")))]
+    pub use pamc::*;
+
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "pamc"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "pamc")))]
+        extern "C" in LIBPAMC {
+            pub fn pamc_start() -> pamc_handle_t;
+
+            pub fn pamc_end(pch: *mut pamc_handle_t) -> c_int;
+
+            pub fn pamc_load(pch: pamc_handle_t, agent_id: *const c_char) -> c_int;
+
+            pub fn pamc_disable(pch: pamc_handle_t, agent_id: *const c_char) -> c_int;
+
+            pub fn pamc_converse(pch: pamc_handle_t, prompt: *mut pamc_bp_t) -> c_int;
+
+            pub fn pamc_list_agents(pch: pamc_handle_t) -> *mut *mut c_char;
+        }
     }
 
     // XSSO 5.2 PAM Status Codes
@@ -386,8 +1044,46 @@ pub mod linux_pam {
     /// please call this function again to complete authentication stack. Before calling again,
     /// verify that conversation is completed
     pub const PAM_INCOMPLETE: c_int = 31;
+
+    /// Add new return values above and change this value accordingly
+    pub const _PAM_RETURN_VALUES: c_int = 32;
     // *** End Linux-PAM extensions
 
+    status_codes! {
+        PAM_SUCCESS => Success,
+        PAM_OPEN_ERR => OpenErr,
+        PAM_SYMBOL_ERR => SymbolErr,
+        PAM_SERVICE_ERR => ServiceErr,
+        PAM_SYSTEM_ERR => SystemErr,
+        PAM_BUF_ERR => BufErr,
+        PAM_PERM_DENIED => PermDenied,
+        PAM_AUTH_ERR => AuthErr,
+        PAM_CRED_INSUFFICIENT => CredInsufficient,
+        PAM_AUTHINFO_UNAVAIL => AuthinfoUnavail,
+        PAM_USER_UNKNOWN => UserUnknown,
+        PAM_MAXTRIES => Maxtries,
+        PAM_NEW_AUTHTOK_REQD => NewAuthtokReqd,
+        PAM_ACCT_EXPIRED => AcctExpired,
+        PAM_SESSION_ERR => SessionErr,
+        PAM_CRED_UNAVAIL => CredUnavail,
+        PAM_CRED_EXPIRED => CredExpired,
+        PAM_CRED_ERR => CredErr,
+        PAM_NO_MODULE_DATA => NoModuleData,
+        PAM_CONV_ERR => ConvErr,
+        PAM_AUTHTOK_ERR => AuthtokErr,
+        PAM_AUTHTOK_RECOVERY_ERR => AuthtokRecoveryErr,
+        PAM_AUTHTOK_LOCK_BUSY => AuthtokLockBusy,
+        PAM_AUTHTOK_DISABLE_AGING => AuthtokDisableAging,
+        PAM_TRY_AGAIN => TryAgain,
+        PAM_IGNORE => Ignore,
+        PAM_ABORT => Abort,
+        PAM_AUTHTOK_EXPIRED => AuthtokExpired,
+        PAM_MODULE_UNKNOWN => ModuleUnknown,
+        PAM_BAD_ITEM => BadItem,
+        PAM_CONV_AGAIN => ConvAgain,
+        PAM_INCOMPLETE => Incomplete,
+    }
+
     // XSSO 5.3 Constants
     pub const PAM_PROMPT_ECHO_OFF: c_int = 1;
     pub const PAM_PROMPT_ECHO_ON: c_int = 2;
@@ -405,6 +1101,56 @@ pub mod linux_pam {
     /// This is for server client non-human interaction.. these are NOT part of the X/Open PAM
     /// specification.
     pub const PAM_BINARY_PROMPT: c_int = 7;
+
+    /// The header of a binary prompt, which is the anonymous struct behind [`pamc_bp_t`].
+    ///
+    /// The data of the prompt starts directly after `control` at offset 5, so it overlaps the
+    /// padding of this struct.
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct pamc_binary_prompt {
+        /// The size of the prompt including the 5 byte header, in big-endian byte order
+        pub length: u32,
+        /// One of the `PAM_BPC_*` control values
+        pub control: u8,
+    }
+
+    /// A binary prompt, as used by [`PAM_BINARY_PROMPT`] messages
+    pub type pamc_bp_t = *mut pamc_binary_prompt;
+
+    // Binary prompt control values. These are used in the control byte of a binary prompt and
+    // are defined by `pam_client.h`.
+    pub const PAM_BPC_FALSE: c_int = 0;
+    pub const PAM_BPC_TRUE: c_int = 1;
+
+    /// continuation packet
+    pub const PAM_BPC_OK: c_int = 0x01;
+    /// initialization packet
+    pub const PAM_BPC_SELECT: c_int = 0x02;
+    /// termination packet
+    pub const PAM_BPC_DONE: c_int = 0x03;
+    /// unable to execute
+    pub const PAM_BPC_FAIL: c_int = 0x04;
+
+    // The following control characters are only legal for exchanges between an agent and a
+    // client
+    /// obtain client env.var
+    pub const PAM_BPC_GETENV: c_int = 0x41;
+    /// set client env.var
+    pub const PAM_BPC_PUTENV: c_int = 0x42;
+    /// display message
+    pub const PAM_BPC_TEXT: c_int = 0x43;
+    /// display error message
+    pub const PAM_BPC_ERROR: c_int = 0x44;
+    /// echo'd text prompt
+    pub const PAM_BPC_PROMPT: c_int = 0x45;
+    /// non-echo'd text prompt
+    pub const PAM_BPC_PASS: c_int = 0x46;
+
+    /// The size of the header of a binary prompt
+    pub const PAM_BP_MIN_SIZE: c_int = 5;
+    /// An advisory limit on the length of the data of a binary prompt
+    pub const PAM_BP_MAX_LENGTH: c_int = 0x20000;
     // *** End Linux-PAM extensions
 
     // XSSO 5.4 Flags
@@ -423,6 +1169,18 @@ pub mod linux_pam {
     // These flags are used by pam_chauthtok
     pub const PAM_CHANGE_EXPIRED_AUTHTOK: c_int = 0x20;
 
+    // These flags are used by pam_sm_chauthtok
+    pub const PAM_PRELIM_CHECK: c_int = 0x4000;
+    pub const PAM_UPDATE_AUTHTOK: c_int = 0x2000;
+
+    // *** Start Linux-PAM extensions
+    // These flags are passed to the cleanup function of pam_set_data()
+    /// used when replacing a data item
+    pub const PAM_DATA_REPLACE: c_int = 0x2000_0000;
+    /// used to suppress messages in the cleanup function
+    pub const PAM_DATA_SILENT: c_int = 0x4000_0000;
+    // *** End Linux-PAM extensions
+
     // XSSO 5.5 Item_type
     pub const PAM_SERVICE: c_int = 1;
     pub const PAM_USER: c_int = 2;
@@ -444,6 +1202,29 @@ pub mod linux_pam {
     /// The type for pam_get_authtok
     pub const PAM_AUTHTOK_TYPE: c_int = 13;
     // *** End Linux-PAM extensions
+
+    mod items {
+        use std::ffi::{c_char, c_int, c_uint, c_void};
+
+        /// The X server authentication data of the `PAM_XAUTHDATA` item
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct pam_xauth_data {
+            pub namelen: c_int,
+            pub name: *mut c_char,
+            pub datalen: c_int,
+            pub data: *mut c_char,
+        }
+
+        /// The function of the `PAM_FAIL_DELAY` item, which replaces the delay after a failed
+        /// authentication.
+        ///
+        /// It receives the status of the failed call, the delay in microseconds and the
+        /// `appdata_ptr` of the conversation.
+        pub type pam_fail_delay_fn = Option<unsafe extern "C" fn(c_int, c_uint, *mut c_void)>;
+    }
+
+    pub use items::*;
 }
 
 pub mod openpam {
@@ -451,139 +1232,114 @@ pub mod openpam {
 
     #[cfg(any(docsrs, feature = "openpam"))]
     use {
-        super::{pam_conv, pam_handle_t},
+        super::{pam_handle_t, pam_message, pam_response, va_list},
         std::ffi::{c_char, c_void},
     };
 
-    #[cfg(any(docsrs, feature = "openpam"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "openpam")))]
-    extern "C" {
-        pub fn openpam_borrow_cred(pamh: *mut pam_handle_t, pwd: *const passwd) -> c_int;
-
-        pub fn openpam_subst(
-            pamh: *const pam_handle_t,
-            buf: *mut c_char,
-            bufsize: usize,
-            template: *const c_char,
-        ) -> c_int;
-
-        pub fn openpam_free_data(pamh: *mut pam_handle_t, data: *const c_void, status: c_int);
-
-        pub fn openpam_free_envlist(envlist: *mut *mut c_char);
-
-        pub fn openpam_get_option(pamh: *mut pam_handle_t, option: *const c_char) -> *const c_char;
-
-        pub fn openpam_restore_cred(pamh: *mut pam_handle_t) -> c_int;
-
-        pub fn openpam_set_option(
-            pamh: *mut pam_handle_t,
-            option: *const c_char,
-            value: *const c_char,
-        ) -> c_int;
-
-        pub fn pam_error(pamh: *const pam_handle_t, fmt: *const c_char, ...) -> c_int;
-
-        pub fn pam_get_authtok(
-            pamh: *mut pam_handle_t,
-            item: c_int,
-            authtok: *const *mut c_char,
-            prompt: *const c_char,
-        ) -> c_int;
-
-        pub fn pam_info(pamh: *const pam_handle_t, fmt: *const char, ...) -> c_int;
-
-        pub fn pam_prompt(
-            pamh: *const pam_handle_t,
-            style: c_int,
-            resp: *mut *mut c_char,
-            fmt: *const char,
-            ...
-        ) -> c_int;
-
-        pub fn pam_setenv(
-            pamh: *mut pam_handle_t,
-            name: *const c_char,
-            value: *const c_char,
-            overwrite: c_int,
-        ) -> c_int;
-
-        // TODO:
-        // I am skipping these since I don't know what to do with the va_list. I know there is a
-        // experimental features.
-        //
-        // int
-        // pam_vinfo(const pam_handle_t *_pamh,
-        //     const char *_fmt,
-        //     va_list _ap)
-        //     OPENPAM_FORMAT ((__printf__, 2, 0))
-        //     OPENPAM_NONNULL((1,2));
-        //
-        // int
-        // pam_verror(const pam_handle_t *_pamh,
-        //     const char *_fmt,
-        //     va_list _ap)
-        //     OPENPAM_FORMAT ((__printf__, 2, 0))
-        //     OPENPAM_NONNULL((1,2));
-        //
-        // int
-        // pam_vprompt(const pam_handle_t *_pamh,
-        //     int _style,
-        //     char **_resp,
-        //     const char *_fmt,
-        //     va_list _ap)
-        //     OPENPAM_FORMAT ((__printf__, 4, 0))
-        //     OPENPAM_NONNULL((1,4));
-
-        pub fn openpam_straddch(
-            str: *mut *mut c_char,
-            sizep: *mut usize,
-            lenp: *mut usize,
-            ch: c_int,
-        ) -> c_int;
-
-        pub fn openpam_set_feature(feature: c_int, onoff: c_int) -> c_int;
-        pub fn openpam_get_feature(feature: c_int, onoff: *mut c_int) -> c_int;
-
-        pub fn _openpam_log(level: c_int, func: *const c_char, fmt: *const c_char, ...);
-
-        // Same here.
-        //
-        // #if defined(__STDC_VERSION__) && (__STDC_VERSION__ >= 199901L)
-        // #define openpam_log(lvl, ...) \
-        // 	_openpam_log((lvl), __func__, __VA_ARGS__)
-        // #elif defined(__GNUC__) && (__GNUC__ >= 3)
-        // #define openpam_log(lvl, ...) \
-        // 	_openpam_log((lvl), __func__, __VA_ARGS__)
-        // #elif defined(__GNUC__) && (__GNUC__ >= 2) && (__GNUC_MINOR__ >= 95)
-        // #define openpam_log(lvl, fmt...) \
-        // 	_openpam_log((lvl), __func__, ##fmt)
-        // #elif defined(__GNUC__) && defined(__FUNCTION__)
-        // #define openpam_log(lvl, fmt...) \
-        // 	_openpam_log((lvl), __FUNCTION__, ##fmt)
-        // #else
-        // void
-        // openpam_log(int _level,
-        // 	const char *_format,
-        // 	...)
-        // 	OPENPAM_FORMAT ((__printf__, 2, 3))
-        // 	OPENPAM_NONNULL((2));
-        // #endif
-
-        pub fn openpam_ttyconv(
-            n: c_int,
-            msg: *const *mut pam_message,
-            resp: *mut *mut pam_response,
-            data: *mut c_void,
-        ) -> c_int;
-
-        pub static openpam_ttyconv_timeout: c_int;
-
-        pub fn openpam_nullconv(
-            n: c_int,
-            msg: *const *mut pam_message,
-            resp: *mut *mut pam_response,
-            data: *mut c_void,
-        ) -> c_int;
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "openpam"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "openpam")))]
+        extern "C" {
+            pub fn openpam_borrow_cred(pamh: *mut pam_handle_t, pwd: *const passwd) -> c_int;
+
+            pub fn openpam_subst(
+                pamh: *const pam_handle_t,
+                buf: *mut c_char,
+                bufsize: *mut usize,
+                template: *const c_char,
+            ) -> c_int;
+
+            pub fn openpam_free_data(pamh: *mut pam_handle_t, data: *mut c_void, status: c_int);
+
+            pub fn openpam_free_envlist(envlist: *mut *mut c_char);
+
+            pub fn openpam_get_option(pamh: *mut pam_handle_t, option: *const c_char) -> *const c_char;
+
+            pub fn openpam_restore_cred(pamh: *mut pam_handle_t) -> c_int;
+
+            pub fn openpam_set_option(
+                pamh: *mut pam_handle_t,
+                option: *const c_char,
+                value: *const c_char,
+            ) -> c_int;
+
+            pub fn pam_error(pamh: *const pam_handle_t, fmt: *const c_char, ...) -> c_int;
+
+            pub fn pam_get_authtok(
+                pamh: *mut pam_handle_t,
+                item: c_int,
+                authtok: *mut *const c_char,
+                prompt: *const c_char,
+            ) -> c_int;
+
+            pub fn pam_info(pamh: *const pam_handle_t, fmt: *const c_char, ...) -> c_int;
+
+            pub fn pam_prompt(
+                pamh: *const pam_handle_t,
+                style: c_int,
+                resp: *mut *mut c_char,
+                fmt: *const c_char,
+                ...
+            ) -> c_int;
+
+            pub fn pam_setenv(
+                pamh: *mut pam_handle_t,
+                name: *const c_char,
+                value: *const c_char,
+                overwrite: c_int,
+            ) -> c_int;
+
+            pub fn pam_verror(
+                pamh: *const pam_handle_t,
+                fmt: *const c_char,
+                args: va_list,
+            ) -> c_int;
+
+            pub fn pam_vinfo(
+                pamh: *const pam_handle_t,
+                fmt: *const c_char,
+                args: va_list,
+            ) -> c_int;
+
+            pub fn pam_vprompt(
+                pamh: *const pam_handle_t,
+                style: c_int,
+                resp: *mut *mut c_char,
+                fmt: *const c_char,
+                args: va_list,
+            ) -> c_int;
+
+            pub fn openpam_straddch(
+                str: *mut *mut c_char,
+                sizep: *mut usize,
+                lenp: *mut usize,
+                ch: c_int,
+            ) -> c_int;
+
+            pub fn openpam_set_feature(feature: c_int, onoff: c_int) -> c_int;
+            pub fn openpam_get_feature(feature: c_int, onoff: *mut c_int) -> c_int;
+
+            pub fn _openpam_log(level: c_int, func: *const c_char, fmt: *const c_char, ...);
+
+            // `openpam_log` is a C macro that passes `__func__` to `_openpam_log`, except on compilers
+            // without variadic macros, so there is no symbol to bind to.
+
+            pub fn openpam_ttyconv(
+                n: c_int,
+                msg: *mut *const pam_message,
+                resp: *mut *mut pam_response,
+                data: *mut c_void,
+            ) -> c_int;
+
+            pub static openpam_ttyconv_timeout: c_int;
+
+            pub fn openpam_nullconv(
+                n: c_int,
+                msg: *mut *const pam_message,
+                resp: *mut *mut pam_response,
+                data: *mut c_void,
+            ) -> c_int;
+        }
     }
 
     #[cfg(any(docsrs, feature = "openpam"))]
@@ -604,9 +1360,11 @@ pub mod openpam {
         pub const PAM_LOG_NOTICE: c_int = 2;
         pub const PAM_LOG_ERROR: c_int = 3;
 
-        pub struct pam_message;
-        pub struct pam_response;
-        pub struct passwd;
+        /// The `struct passwd` from `pwd.h`
+        #[repr(C)]
+        pub struct passwd {
+            _private: [u8; 0],
+        }
     }
 
     #[cfg(any(docsrs, feature = "openpam"))]
@@ -616,26 +1374,28 @@ pub mod openpam {
     #[cfg(any(docsrs, feature = "read_cooked_lines"))]
     use libc::FILE;
 
-    #[cfg(any(docsrs, feature = "read_cooked_lines"))]
-    #[cfg_attr(docsrs, doc(cfg(feature = "read_cooked_lines")))]
-    extern "C" {
-        pub fn openpam_readline(
-            f: *mut FILE,
-            lineno: *mut c_int,
-            lenp: *mut usize,
-        ) -> *mut c_char;
-
-        pub fn openpam_readlinev(
-            f: *mut FILE,
-            lineno: *mut c_int,
-            lenp: *mut c_int,
-        ) -> *mut *mut c_char;
-
-        pub fn openpam_readword(
-            f: *mut FILE,
-            lineno: *mut c_int,
-            lenp: *mut usize,
-        ) -> *mut c_char;
+    pam_extern! {
+        #[cfg(any(docsrs, feature = "read_cooked_lines"))]
+        #[cfg_attr(docsrs, doc(cfg(feature = "read_cooked_lines")))]
+        extern "C" {
+            pub fn openpam_readline(
+                f: *mut FILE,
+                lineno: *mut c_int,
+                lenp: *mut usize,
+            ) -> *mut c_char;
+
+            pub fn openpam_readlinev(
+                f: *mut FILE,
+                lineno: *mut c_int,
+                lenp: *mut c_int,
+            ) -> *mut *mut c_char;
+
+            pub fn openpam_readword(
+                f: *mut FILE,
+                lineno: *mut c_int,
+                lenp: *mut usize,
+            ) -> *mut c_char;
+        }
     }
 
     // XSSO 5.2 PAM Status Codes
@@ -678,9 +1438,46 @@ pub mod openpam {
     pub const PAM_BAD_ITEM: c_int = 31;
     pub const PAM_BAD_FEATURE: c_int = 32;
     pub const PAM_BAD_CONSTANT: c_int = 33;
-    pub const PAM_NUM_ERRORS: c_int = 33;
+    pub const PAM_NUM_ERRORS: c_int = 34;
     // *** End OpenPAM extension
 
+    status_codes! {
+        PAM_SUCCESS => Success,
+        PAM_OPEN_ERR => OpenErr,
+        PAM_SYMBOL_ERR => SymbolErr,
+        PAM_SERVICE_ERR => ServiceErr,
+        PAM_SYSTEM_ERR => SystemErr,
+        PAM_BUF_ERR => BufErr,
+        PAM_CONV_ERR => ConvErr,
+        PAM_PERM_DENIED => PermDenied,
+        PAM_MAXTRIES => Maxtries,
+        PAM_AUTH_ERR => AuthErr,
+        PAM_NEW_AUTHTOK_REQD => NewAuthtokReqd,
+        PAM_CRED_INSUFFICIENT => CredInsufficient,
+        PAM_AUTHINFO_UNAVAIL => AuthinfoUnavail,
+        PAM_USER_UNKNOWN => UserUnknown,
+        PAM_CRED_UNAVAIL => CredUnavail,
+        PAM_CRED_EXPIRED => CredExpired,
+        PAM_CRED_ERR => CredErr,
+        PAM_ACCT_EXPIRED => AcctExpired,
+        PAM_AUTHTOK_EXPIRED => AuthtokExpired,
+        PAM_SESSION_ERR => SessionErr,
+        PAM_AUTHTOK_ERR => AuthtokErr,
+        PAM_AUTHTOK_RECOVERY_ERR => AuthtokRecoveryErr,
+        PAM_AUTHTOK_LOCK_BUSY => AuthtokLockBusy,
+        PAM_AUTHTOK_DISABLE_AGING => AuthtokDisableAging,
+        PAM_NO_MODULE_DATA => NoModuleData,
+        PAM_IGNORE => Ignore,
+        PAM_ABORT => Abort,
+        PAM_TRY_AGAIN => TryAgain,
+        PAM_MODULE_UNKNOWN => ModuleUnknown,
+        PAM_DOMAIN_UNKNOWN => DomainUnknown,
+        PAM_BAD_HANDLE => BadHandle,
+        PAM_BAD_ITEM => BadItem,
+        PAM_BAD_FEATURE => BadFeature,
+        PAM_BAD_CONSTANT => BadConstant,
+    }
+
     // XSSO 5.3 Constants
     pub const PAM_PROMPT_ECHO_OFF: c_int = 1;
     pub const PAM_PROMPT_ECHO_ON: c_int = 2;
@@ -692,7 +1489,7 @@ pub mod openpam {
 
     // XSSO 5.4 Flags
     // General Flags
-    pub const PAM_SILENT: c_int = 0x7999_9999 + 1;
+    pub const PAM_SILENT: c_int = c_int::MIN;
 
     // Flags for pam_authenticate
     pub const PAM_DISALLOW_NULL_AUTHTOK: c_int = 0x1;
@@ -728,4 +1525,19 @@ pub mod openpam {
     pub const PAM_HOST: c_int = 13;
     pub const PAM_NUM_ITEMS: c_int = 14;
     // End OpenPAM extension
+
+    mod items {
+        use std::ffi::{c_char, c_void};
+
+        /// The user database of the `PAM_REPOSITORY` item
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct pam_repository {
+            pub type_: *mut c_char,
+            pub scope: *mut c_void,
+            pub scope_len: usize,
+        }
+    }
+
+    pub use items::*;
 }
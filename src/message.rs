@@ -0,0 +1,114 @@
+//! Reading the messages that are passed to a conversation function.
+//!
+//! The `msg` argument of a conversation function is declared as `const struct pam_message **msg`,
+//! but callers disagree on what it points to. Linux-PAM and OpenPAM pass an array of pointers to
+//! messages, while Solaris-derived implementations pass a pointer to an array of messages. With a
+//! single message both layouts are the same, so code that only indexes `msg[i]` appears to work
+//! until it receives several messages from the other kind of caller.
+//!
+//! [`Messages`] iterates over the messages with the layout of the implementation selected at build
+//! time, or with an explicit [`MessageLayout`]. A null pointer in place of a message is yielded as
+//! `None`, so the conversation function can fail instead of reading it.
+
+use std::ffi::c_int;
+use std::marker::PhantomData;
+use std::ptr;
+
+use crate::pam_message;
+
+/// How the `msg` argument of a conversation function points to the messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLayout {
+    /// `msg` points to an array of pointers to messages, which is `msg[i]` in C
+    Pointers,
+    /// `msg` points to a pointer to an array of messages, which is `(*msg)[i]` in C
+    Array,
+}
+
+impl MessageLayout {
+    /// The layout used by the PAM implementation this crate was built for.
+    ///
+    /// Both Linux-PAM and OpenPAM pass an array of pointers.
+    #[cfg(any(pam_impl = "linux-pam", pam_impl = "openpam"))]
+    pub const NATIVE: Self = Self::Pointers;
+}
+
+/// An iterator over the messages passed to a conversation function.
+///
+/// Every message is yielded as `None` when its pointer is null, which a well-behaved caller never
+/// passes.
+#[derive(Debug, Clone)]
+pub struct Messages<'a> {
+    msg: *const *const pam_message,
+    layout: MessageLayout,
+    index: usize,
+    count: usize,
+    _messages: PhantomData<&'a pam_message>,
+}
+
+impl<'a> Messages<'a> {
+    /// Iterate over `num_msg` messages with the [`MessageLayout::NATIVE`] layout.
+    ///
+    /// # Safety
+    ///
+    /// See [`Messages::with_layout`].
+    pub unsafe fn new(num_msg: c_int, msg: *const *const pam_message) -> Self {
+        Self::with_layout(num_msg, msg, MessageLayout::NATIVE)
+    }
+
+    /// Iterate over `num_msg` messages that are laid out as `layout`.
+    ///
+    /// A negative `num_msg` or a null `msg` is treated as no messages.
+    ///
+    /// # Safety
+    ///
+    /// `msg` has to point to `num_msg` messages in the given layout, which stay valid for `'a`.
+    /// Each message may be a null pointer instead.
+    pub unsafe fn with_layout(
+        num_msg: c_int,
+        msg: *const *const pam_message,
+        layout: MessageLayout,
+    ) -> Self {
+        let count = if msg.is_null() {
+            0
+        } else {
+            usize::try_from(num_msg).unwrap_or(0)
+        };
+
+        Self {
+            msg,
+            layout,
+            index: 0,
+            count,
+            _messages: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Option<&'a pam_message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let message = unsafe {
+            match self.layout {
+                MessageLayout::Pointers => *self.msg.add(self.index),
+                MessageLayout::Array if (*self.msg).is_null() => ptr::null(),
+                MessageLayout::Array => (*self.msg).add(self.index),
+            }
+        };
+        self.index += 1;
+
+        Some(unsafe { message.as_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Messages<'_> {}
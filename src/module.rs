@@ -0,0 +1,151 @@
+//! Support for writing PAM service modules in Rust.
+//!
+//! A PAM service module is a shared object that exports some of the `pam_sm_*` entry points. The
+//! [`PamServiceModule`] trait describes these entry points, and the [`pam_module!`] macro exports
+//! them with the correct symbol names.
+//!
+//! ```no_run
+//! use std::ffi::{c_int, CStr};
+//!
+//! use libpam_sys::module::PamServiceModule;
+//! use libpam_sys::{pam_handle_t, pam_module, PAM_SUCCESS};
+//!
+//! struct AllowAll;
+//!
+//! impl PamServiceModule for AllowAll {
+//!     fn authenticate(_pamh: *mut pam_handle_t, _flags: c_int, _args: &[&CStr]) -> c_int {
+//!         PAM_SUCCESS
+//!     }
+//! }
+//!
+//! pam_module!(AllowAll);
+//! ```
+//!
+//! [`pam_module!`]: crate::pam_module
+
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{pam_handle_t, PAM_IGNORE, PAM_SERVICE_ERR};
+
+/// The hooks of a PAM service module.
+///
+/// Every hook defaults to returning [`PAM_IGNORE`], so a module only has to implement the hooks
+/// for the management groups it supports. The `args` slice contains the module arguments given in
+/// the PAM configuration file.
+pub trait PamServiceModule {
+    /// Called for `pam_authenticate`.
+    fn authenticate(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+
+    /// Called for `pam_setcred`.
+    fn setcred(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+
+    /// Called for `pam_acct_mgmt`.
+    fn acct_mgmt(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+
+    /// Called for `pam_open_session`.
+    fn open_session(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+
+    /// Called for `pam_close_session`.
+    fn close_session(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+
+    /// Called for `pam_chauthtok`.
+    fn chauthtok(pamh: *mut pam_handle_t, flags: c_int, args: &[&CStr]) -> c_int {
+        let _ = (pamh, flags, args);
+        PAM_IGNORE
+    }
+}
+
+/// Signature of the `pam_sm_*` entry points that a service module exports.
+pub type pam_sm_fn = unsafe extern "C" fn(
+    pamh: *mut pam_handle_t,
+    flags: c_int,
+    argc: c_int,
+    argv: *mut *const c_char,
+) -> c_int;
+
+/// Forward a `pam_sm_*` call to a Rust hook.
+///
+/// This is used by [`pam_module!`](crate::pam_module) and is not meant to be called directly.
+///
+/// # Safety
+///
+/// `argv` has to point to `argc` valid nul-terminated strings, or be null.
+#[doc(hidden)]
+pub unsafe fn __dispatch(
+    pamh: *mut pam_handle_t,
+    flags: c_int,
+    argc: c_int,
+    argv: *mut *const c_char,
+    hook: fn(*mut pam_handle_t, c_int, &[&CStr]) -> c_int,
+) -> c_int {
+    // Panics should never unwind into libpam.
+    catch_unwind(AssertUnwindSafe(|| {
+        let argc = usize::try_from(argc).unwrap_or(0);
+        let args: Vec<&CStr> = if argv.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(argv, argc)
+                .iter()
+                .filter(|arg| !arg.is_null())
+                .map(|&arg| CStr::from_ptr(arg))
+                .collect()
+        };
+
+        hook(pamh, flags, &args)
+    }))
+    .unwrap_or(PAM_SERVICE_ERR)
+}
+
+/// Export the `pam_sm_*` entry points for a type implementing
+/// [`PamServiceModule`](crate::module::PamServiceModule).
+///
+/// This should be invoked once in a `cdylib` crate. Hooks that are not implemented by the type
+/// return `PAM_IGNORE`, and panics in any hook are turned into `PAM_SERVICE_ERR`.
+#[macro_export]
+macro_rules! pam_module {
+    ($module:ty) => {
+        $crate::pam_module!(@export $module,
+            pam_sm_authenticate => authenticate,
+            pam_sm_setcred => setcred,
+            pam_sm_acct_mgmt => acct_mgmt,
+            pam_sm_open_session => open_session,
+            pam_sm_close_session => close_session,
+            pam_sm_chauthtok => chauthtok,
+        );
+    };
+    (@export $module:ty, $($symbol:ident => $hook:ident),* $(,)?) => {
+        $(
+            #[no_mangle]
+            pub unsafe extern "C" fn $symbol(
+                pamh: *mut $crate::pam_handle_t,
+                flags: ::std::ffi::c_int,
+                argc: ::std::ffi::c_int,
+                argv: *mut *const ::std::ffi::c_char,
+            ) -> ::std::ffi::c_int {
+                $crate::module::__dispatch(
+                    pamh,
+                    flags,
+                    argc,
+                    argv,
+                    <$module as $crate::module::PamServiceModule>::$hook,
+                )
+            }
+        )*
+    };
+}
@@ -0,0 +1,92 @@
+//! Runtime resolution of the functions that the linked libpam may be too old for.
+//!
+//! The build script only emits a cfg like `pam_linux_1_4` when the library is at least that
+//! release. Functions that were added later are declared with `#[since(...)]` in `pam_extern!`,
+//! and are looked up with `dlsym` when the cfg is missing. A library that is newer at runtime than
+//! at build time still provides them.
+
+use std::ffi::{c_char, c_int, c_void};
+use std::sync::OnceLock;
+
+/// `RTLD_DEFAULT` on Linux, which searches the global symbols of the process
+const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+
+extern "C" {
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+/// A function of libpam that is resolved the first time it is used.
+pub struct OptionalSymbol<T> {
+    /// The nul-terminated name of the symbol
+    name: &'static str,
+    value: OnceLock<Option<T>>,
+}
+
+impl<T: Copy> OptionalSymbol<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            value: OnceLock::new(),
+        }
+    }
+
+    /// The function, or `None` when the loaded libpam does not export it
+    pub fn get(&self) -> Option<T> {
+        *self.value.get_or_init(|| {
+            // Reference libpam, so it is not dropped from the binary when linking with `--as-needed`
+            std::hint::black_box(crate::pam_start as *const ());
+
+            let symbol = unsafe { dlsym(RTLD_DEFAULT, self.name.as_ptr().cast()) };
+            (!symbol.is_null()).then(|| unsafe { std::mem::transmute_copy(&symbol) })
+        })
+    }
+}
+
+/// The value returned in place of a missing function.
+pub trait Missing {
+    fn missing() -> Self;
+}
+
+impl Missing for c_int {
+    fn missing() -> Self {
+        crate::PAM_SYMBOL_ERR
+    }
+}
+
+impl<T> Missing for *mut T {
+    fn missing() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Call `symbol` like the functions declared with `#[since(...)]` do.
+    fn call<T: Missing>(symbol: &OptionalSymbol<unsafe extern "C" fn() -> T>) -> T {
+        match symbol.get() {
+            Some(function) => unsafe { function() },
+            None => Missing::missing(),
+        }
+    }
+
+    #[test]
+    fn missing_symbol() {
+        static STATUS: OptionalSymbol<unsafe extern "C" fn() -> c_int> =
+            OptionalSymbol::new("pam_libpam_sys_missing\0");
+        static POINTER: OptionalSymbol<unsafe extern "C" fn() -> *mut c_char> =
+            OptionalSymbol::new("pam_libpam_sys_missing\0");
+
+        assert!(STATUS.get().is_none());
+        assert_eq!(call(&STATUS), crate::PAM_SYMBOL_ERR);
+        assert!(call(&POINTER).is_null());
+    }
+
+    #[test]
+    fn present_symbol() {
+        static SYMBOL: OptionalSymbol<unsafe extern "C" fn()> =
+            OptionalSymbol::new("pam_getenvlist\0");
+        assert!(SYMBOL.get().is_some());
+    }
+}
@@ -0,0 +1,195 @@
+//! Binary prompts of the Linux-PAM agent protocol.
+//!
+//! A binary prompt is a 5 byte header followed by the data of the prompt. The header contains the
+//! size of the whole prompt in big-endian byte order and a control byte with one of the
+//! `PAM_BPC_*` values. Binary prompts are exchanged with agents through [`pamc_converse`], and
+//! they are sent to the application as the `msg` of a [`PAM_BINARY_PROMPT`] message.
+//!
+//! [`BinaryPrompt`] builds and parses these prompts, so the header never has to be written by
+//! hand.
+//!
+//! ```
+//! use libpam_sys::linux_pam::{PAM_BPC_OK, PAM_BPC_SELECT};
+//! use libpam_sys::pamc::BinaryPrompt;
+//!
+//! let prompt = BinaryPrompt::new(PAM_BPC_SELECT as u8, b"secret@here/challenge".to_vec())?;
+//! let bytes = prompt.to_bytes();
+//!
+//! let parsed = BinaryPrompt::from_bytes(&bytes)?;
+//! assert_eq!(parsed, prompt);
+//! assert_ne!(parsed.control(), PAM_BPC_OK as u8);
+//! # Ok::<(), libpam_sys::pamc::PromptError>(())
+//! ```
+//!
+//! [`pamc_converse`]: crate::linux_pam::pamc_converse
+//! [`PAM_BINARY_PROMPT`]: crate::linux_pam::PAM_BINARY_PROMPT
+
+use std::fmt;
+use std::ptr;
+
+use crate::linux_pam::{pamc_bp_t, PAM_BP_MIN_SIZE};
+
+const HEADER_SIZE: usize = PAM_BP_MIN_SIZE as usize;
+
+/// An error that occurred while building or parsing a binary prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    /// The prompt is a null pointer
+    Null,
+    /// The prompt is shorter than its header
+    TooShort { size: usize },
+    /// The size in the header does not match the size of the prompt
+    SizeMismatch { header: usize, actual: usize },
+    /// The data does not fit in a prompt
+    TooLong { length: usize },
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "Binary prompt is a null pointer"),
+            Self::TooShort { size } => write!(
+                f,
+                "Binary prompt of {size} bytes is shorter than its {HEADER_SIZE} byte header"
+            ),
+            Self::SizeMismatch { header, actual } => write!(
+                f,
+                "Binary prompt header has a size of {header} bytes, but the prompt has {actual}"
+            ),
+            Self::TooLong { length } => {
+                write!(f, "{length} bytes of data do not fit in a binary prompt")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+/// A binary prompt, consisting of a control byte and data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryPrompt {
+    control: u8,
+    data: Vec<u8>,
+}
+
+impl BinaryPrompt {
+    /// Create a prompt with one of the `PAM_BPC_*` values as `control`.
+    pub fn new(control: u8, data: Vec<u8>) -> Result<Self, PromptError> {
+        if u32::try_from(HEADER_SIZE + data.len()).is_err() {
+            return Err(PromptError::TooLong { length: data.len() });
+        }
+
+        Ok(Self { control, data })
+    }
+
+    /// The control byte
+    pub fn control(&self) -> u8 {
+        self.control
+    }
+
+    /// The data after the header
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Take the data out of the prompt.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// The size of the prompt including its header
+    pub fn size(&self) -> usize {
+        HEADER_SIZE + self.data.len()
+    }
+
+    /// Parse a prompt from its raw bytes, including the header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PromptError> {
+        let Some((&[a, b, c, d, control], data)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+            return Err(PromptError::TooShort { size: bytes.len() });
+        };
+
+        let header = u32::from_be_bytes([a, b, c, d]) as usize;
+        if header != bytes.len() {
+            return Err(PromptError::SizeMismatch {
+                header,
+                actual: bytes.len(),
+            });
+        }
+
+        Ok(Self {
+            control,
+            data: data.to_vec(),
+        })
+    }
+
+    /// The raw bytes of the prompt, including the header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.size());
+        // `new` made sure that the size fits
+        bytes.extend_from_slice(&(self.size() as u32).to_be_bytes());
+        bytes.push(self.control);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Parse a prompt that was allocated by libpam, libpamc or an agent.
+    ///
+    /// # Safety
+    ///
+    /// `prompt` has to be null or point to a binary prompt of which the header contains the
+    /// correct size.
+    pub unsafe fn from_raw(prompt: pamc_bp_t) -> Result<Self, PromptError> {
+        if prompt.is_null() {
+            return Err(PromptError::Null);
+        }
+
+        let prompt = prompt.cast::<u8>();
+        let header = ptr::read_unaligned(prompt.cast::<[u8; 4]>());
+        let size = u32::from_be_bytes(header) as usize;
+        if size < HEADER_SIZE {
+            return Err(PromptError::TooShort { size });
+        }
+
+        Self::from_bytes(std::slice::from_raw_parts(prompt, size))
+    }
+
+    /// Copy the prompt into memory allocated with `malloc`.
+    ///
+    /// This is the memory that [`pamc_converse`] and the `resp` of a [`PAM_BINARY_PROMPT`]
+    /// response expect, and it can be released with [`free_raw`]. Returns a null pointer when the
+    /// allocation fails.
+    ///
+    /// [`pamc_converse`]: crate::linux_pam::pamc_converse
+    /// [`PAM_BINARY_PROMPT`]: crate::linux_pam::PAM_BINARY_PROMPT
+    pub fn to_raw(&self) -> pamc_bp_t {
+        let bytes = self.to_bytes();
+
+        unsafe {
+            let prompt = libc::malloc(bytes.len()).cast::<u8>();
+            if !prompt.is_null() {
+                ptr::copy_nonoverlapping(bytes.as_ptr(), prompt, bytes.len());
+            }
+            prompt.cast()
+        }
+    }
+}
+
+/// Overwrite a prompt with zeros and free it, like `PAM_BP_RENEW(&prompt, 0, 0)` does.
+///
+/// # Safety
+///
+/// `prompt` has to be null or a binary prompt allocated with `malloc` of which the header contains
+/// the correct size. It must not be used afterwards.
+pub unsafe fn free_raw(prompt: pamc_bp_t) {
+    if prompt.is_null() {
+        return;
+    }
+
+    let bytes = prompt.cast::<u8>();
+    let size = u32::from_be_bytes(ptr::read_unaligned(bytes.cast::<[u8; 4]>())) as usize;
+    for i in 0..size.max(HEADER_SIZE) {
+        ptr::write_volatile(bytes.add(i), 0);
+    }
+
+    libc::free(prompt.cast());
+}
@@ -0,0 +1,270 @@
+//! Allocation of conversation responses that may contain secrets.
+//!
+//! libpam releases the responses of a conversation with `free`, so they have to be allocated with
+//! `malloc`. Responses often contain passwords, so every helper in this module overwrites the
+//! memory with zeros before it is freed.
+//!
+//! ```no_run
+//! use std::ffi::c_int;
+//!
+//! use libpam_sys::pam_response;
+//! use libpam_sys::response::Responses;
+//!
+//! fn answer(resp: *mut *mut pam_response) -> Result<(), libpam_sys::response::AllocError> {
+//!     let mut responses = Responses::new(2)?;
+//!     responses.set(0, b"user")?;
+//!     responses.set(1, b"hunter2")?;
+//!
+//!     // libpam now owns the responses
+//!     unsafe { *resp = responses.into_raw() };
+//!     Ok(())
+//! }
+//! ```
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::fmt;
+use std::mem::{size_of, MaybeUninit};
+use std::ops::Deref;
+use std::ptr;
+
+use crate::error::PamError;
+use crate::{pam_get_item, pam_handle_t, pam_response};
+
+pub use crate::wipe::wipe;
+
+/// Overwrite the nul-terminated string `s` with zeros.
+///
+/// # Safety
+///
+/// `s` has to be null or point to a writable nul-terminated string.
+pub unsafe fn wipe_c_str(s: *mut c_char) {
+    if !s.is_null() {
+        wipe(std::slice::from_raw_parts_mut(s.cast(), libc::strlen(s)));
+    }
+}
+
+/// The allocation of a response failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to allocate a PAM response")
+    }
+}
+
+impl std::error::Error for AllocError {}
+
+/// Bytes that are overwritten with zeros when they are dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Vec<u8>);
+
+impl Secret {
+    /// Copy `bytes` into a new secret.
+    pub fn new(bytes: &[u8]) -> Self {
+        // An exact capacity, so the secret is never moved to a new allocation
+        let mut secret = Vec::with_capacity(bytes.len());
+        secret.extend_from_slice(bytes);
+        Self(secret)
+    }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Deref for Secret {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({} bytes)", self.0.len())
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+
+        // Also wipe the spare capacity, which may hold bytes of an earlier length
+        for byte in self.0.spare_capacity_mut() {
+            unsafe { ptr::write_volatile(byte, MaybeUninit::new(0)) };
+        }
+    }
+}
+
+/// An array of [`pam_response`]s allocated with `calloc`.
+///
+/// The responses are overwritten and freed when this is dropped, unless they were handed over to
+/// libpam with [`Responses::into_raw`].
+pub struct Responses {
+    responses: *mut pam_response,
+    /// The number of bytes allocated for every `resp`
+    sizes: Vec<usize>,
+}
+
+impl Responses {
+    /// Allocate `n` empty responses.
+    pub fn new(n: usize) -> Result<Self, AllocError> {
+        // `calloc` may return a null pointer for zero elements
+        let responses = unsafe { libc::calloc(n.max(1), size_of::<pam_response>()) };
+        if responses.is_null() {
+            return Err(AllocError);
+        }
+
+        Ok(Self {
+            responses: responses.cast(),
+            sizes: vec![0; n],
+        })
+    }
+
+    /// The number of responses
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns whether there are no responses.
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Set the `resp` of the response at `index` to a nul-terminated copy of `resp`.
+    ///
+    /// The bytes are copied as is, so binary prompts can be returned as well. A previous `resp` is
+    /// overwritten and freed.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index` is out of bounds.
+    pub fn set(&mut self, index: usize, resp: &[u8]) -> Result<(), AllocError> {
+        assert!(index < self.len(), "Response {index} is out of bounds");
+
+        let size = resp.len() + 1;
+        let copy = unsafe { libc::malloc(size) }.cast::<u8>();
+        if copy.is_null() {
+            return Err(AllocError);
+        }
+
+        unsafe {
+            ptr::copy_nonoverlapping(resp.as_ptr(), copy, resp.len());
+            *copy.add(resp.len()) = 0;
+
+            self.free(index);
+            (*self.responses.add(index)).resp = copy.cast();
+        }
+        self.sizes[index] = size;
+
+        Ok(())
+    }
+
+    /// Set the `resp_retcode` of the response at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index` is out of bounds.
+    pub fn set_retcode(&mut self, index: usize, retcode: c_int) {
+        assert!(index < self.len(), "Response {index} is out of bounds");
+        unsafe { (*self.responses.add(index)).resp_retcode = retcode };
+    }
+
+    /// Hand the responses over, for example to libpam as the `resp` of a conversation.
+    ///
+    /// The array and every `resp` have to be released with `free`, preferably with
+    /// [`free_responses`].
+    pub fn into_raw(self) -> *mut pam_response {
+        let responses = self.responses;
+        std::mem::forget(self);
+        responses
+    }
+
+    /// Overwrite and free the `resp` at `index`.
+    unsafe fn free(&mut self, index: usize) {
+        let response = &mut *self.responses.add(index);
+        if !response.resp.is_null() {
+            wipe(std::slice::from_raw_parts_mut(
+                response.resp.cast(),
+                self.sizes[index],
+            ));
+            libc::free(response.resp.cast());
+            response.resp = ptr::null_mut();
+        }
+    }
+}
+
+impl fmt::Debug for Responses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Responses")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Responses {
+    fn drop(&mut self) {
+        unsafe {
+            for index in 0..self.len() {
+                self.free(index);
+            }
+            libc::free(self.responses.cast());
+        }
+    }
+}
+
+/// Overwrite and free `n` responses that were returned by a conversation.
+///
+/// Every `resp` is overwritten up to its first nul byte, because the array does not record how
+/// long the responses are. The bytes after a nul byte in a binary response are freed without being
+/// overwritten, so callers that receive binary responses should [`wipe`] them first.
+///
+/// # Safety
+///
+/// `responses` has to be null or an array of `n` responses of which the array and every `resp`
+/// were allocated with `malloc`. They must not be used afterwards.
+pub unsafe fn free_responses(responses: *mut pam_response, n: usize) {
+    if responses.is_null() {
+        return;
+    }
+
+    for index in 0..n {
+        let response = &mut *responses.add(index);
+        wipe_c_str(response.resp);
+        libc::free(response.resp.cast());
+    }
+
+    libc::free(responses.cast());
+}
+
+/// Copy a string item, such as `PAM_AUTHTOK`, out of libpam and overwrite the copy of libpam with
+/// zeros.
+///
+/// This is for applications that consume the item. Afterwards, libpam returns an empty string for
+/// the item. Returns `Ok(None)` when the item is not set, and the error of `pam_get_item` when it
+/// fails.
+///
+/// # Safety
+///
+/// `pamh` has to be a valid handle, and `item_type` has to be an item that contains a string.
+pub unsafe fn consume_item(
+    pamh: *mut pam_handle_t,
+    item_type: c_int,
+) -> Result<Option<Secret>, PamError> {
+    let mut item: *const c_void = ptr::null();
+    PamError::check(pam_get_item(pamh, item_type, &mut item))?;
+
+    if item.is_null() {
+        return Ok(None);
+    }
+
+    let secret = Secret::new(CStr::from_ptr(item.cast()).to_bytes());
+    // libpam allocated the item with `strdup`, so it is writable
+    wipe_c_str(item.cast_mut().cast());
+
+    Ok(Some(secret))
+}
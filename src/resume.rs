@@ -0,0 +1,466 @@
+//! Event driven transactions with `PAM_CONV_AGAIN` and `PAM_INCOMPLETE`.
+//!
+//! Linux-PAM lets a conversation return [`PAM_CONV_AGAIN`] when the answers of the user are not
+//! available yet. Modules that support this return [`PAM_INCOMPLETE`], and `pam_authenticate` or
+//! `pam_chauthtok` continue where they stopped when they are called again. [`Resumable`] drives
+//! this flow. It yields the messages of the conversation to the caller, and resumes the call once
+//! the answers are supplied, so no thread has to wait for the user.
+//!
+//! Messages that only inform the user are answered right away, and are returned with the next
+//! [`Progress`].
+//!
+//! ```no_run
+//! use libpam_sys::flags::AuthFlags;
+//! use libpam_sys::resume::{Progress, Resumable};
+//!
+//! let mut transaction = Resumable::start(c"login", Some(c"user"))?;
+//! let mut progress = transaction.authenticate(AuthFlags::empty())?;
+//!
+//! while let Progress::Pending(messages) = progress {
+//!     // Show the messages, and wait for the answers without blocking
+//!     let answers = messages
+//!         .iter()
+//!         .filter(|message| message.is_prompt())
+//!         .map(|_| c"hunter2".to_owned())
+//!         .collect();
+//!
+//!     progress = transaction.resume(answers)?;
+//! }
+//! # Ok::<(), libpam_sys::error::PamError>(())
+//! ```
+//!
+//! [`PAM_CONV_AGAIN`]: crate::linux_pam::PAM_CONV_AGAIN
+//! [`PAM_INCOMPLETE`]: crate::linux_pam::PAM_INCOMPLETE
+
+use std::ffi::{c_int, c_void, CStr, CString};
+use std::fmt;
+use std::ptr;
+
+use crate::error::PamError;
+use crate::flags::{AuthFlags, ChauthtokFlags};
+use crate::handle::PamHandle;
+use crate::linux_pam::{PAM_CONV_AGAIN, PAM_RADIO_TYPE};
+use crate::message::Messages;
+use crate::response::{Responses, Secret};
+use crate::{
+    pam_conv, pam_message, pam_response, PAM_BUF_ERR, PAM_CONV_ERR, PAM_ERROR_MSG, PAM_MAX_NUM_MSG,
+    PAM_MAX_RESP_SIZE, PAM_PROMPT_ECHO_OFF, PAM_PROMPT_ECHO_ON, PAM_SUCCESS, PAM_TEXT_INFO,
+};
+
+/// A message of the conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    style: c_int,
+    text: CString,
+}
+
+impl Message {
+    /// The `msg_style` of the message
+    pub fn style(&self) -> c_int {
+        self.style
+    }
+
+    /// The text of the message
+    pub fn text(&self) -> &CStr {
+        &self.text
+    }
+
+    /// Returns whether the message asks the user for an answer.
+    pub fn is_prompt(&self) -> bool {
+        is_prompt(self.style)
+    }
+}
+
+fn is_prompt(style: c_int) -> bool {
+    matches!(
+        style,
+        PAM_PROMPT_ECHO_ON | PAM_PROMPT_ECHO_OFF | PAM_RADIO_TYPE
+    )
+}
+
+/// The outcome of a call of a [`Resumable`] transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// The call finished successfully, after showing these messages
+    Done(Vec<Message>),
+    /// The call waits for answers to the prompts among these messages, which are passed to
+    /// [`Resumable::resume`]
+    Pending(Vec<Message>),
+}
+
+/// A call that can be resumed, with its flags.
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Authenticate(AuthFlags),
+    Chauthtok(ChauthtokFlags),
+}
+
+/// The conversation state that libpam receives as the `appdata_ptr`.
+#[derive(Default)]
+struct State {
+    /// Informational messages that were answered already
+    shown: Vec<Message>,
+    /// The messages of the conversation that returned `PAM_CONV_AGAIN`
+    pending: Vec<Message>,
+    /// The answers to the prompts of `pending`, once they are supplied
+    answers: Option<Vec<Secret>>,
+}
+
+impl State {
+    /// Collect the messages of a call that finished with `result`.
+    fn progress(&mut self, result: Result<(), PamError>) -> Result<Progress, PamError> {
+        // Unused answers are not carried over to the next call
+        self.answers = None;
+        let mut messages = std::mem::take(&mut self.shown);
+
+        match result {
+            Err(PamError::Incomplete) if !self.pending.is_empty() => {
+                messages.append(&mut self.pending);
+                Ok(Progress::Pending(messages))
+            }
+            Ok(()) => Ok(Progress::Done(messages)),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Owns the conversation state, which has to outlive the handle.
+struct StatePtr(*mut State);
+
+impl Drop for StatePtr {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.0) });
+    }
+}
+
+/// A PAM transaction whose conversation does not block.
+pub struct Resumable {
+    // Declared before `state`, so the transaction is ended before the state is freed
+    pamh: PamHandle,
+    state: StatePtr,
+    operation: Option<Operation>,
+}
+
+impl fmt::Debug for Resumable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resumable")
+            .field("pamh", &self.pamh)
+            .field("operation", &self.operation)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Resumable {
+    /// Start a transaction for `service` with `pam_start`.
+    pub fn start(service: &CStr, user: Option<&CStr>) -> Result<Self, PamError> {
+        let state = StatePtr(Box::into_raw(Box::default()));
+        let conv = pam_conv {
+            conv: converse,
+            app_dataptr: state.0.cast(),
+        };
+
+        Ok(Self {
+            pamh: PamHandle::start(service, user, &conv)?,
+            state,
+            operation: None,
+        })
+    }
+
+    /// The handle of the transaction, for the calls that cannot be resumed.
+    ///
+    /// The conversation of these calls can only show informational messages, prompts fail with
+    /// `PAM_CONV_AGAIN`.
+    pub fn handle(&mut self) -> &mut PamHandle {
+        &mut self.pamh
+    }
+
+    /// Authenticate the user with `pam_authenticate`.
+    pub fn authenticate(&mut self, flags: AuthFlags) -> Result<Progress, PamError> {
+        self.run(Operation::Authenticate(flags))
+    }
+
+    /// Change the authentication token of the user with `pam_chauthtok`.
+    pub fn chauthtok(&mut self, flags: ChauthtokFlags) -> Result<Progress, PamError> {
+        self.run(Operation::Chauthtok(flags))
+    }
+
+    /// Continue the pending call with one answer for every prompt of [`Progress::Pending`].
+    ///
+    /// Fails with [`PamError::SystemErr`] when no call is pending.
+    pub fn resume(&mut self, answers: Vec<CString>) -> Result<Progress, PamError> {
+        let Some(operation) = self.operation else {
+            return Err(PamError::SystemErr);
+        };
+
+        let answers = answers
+            .into_iter()
+            .map(|answer| Secret::from(answer.into_bytes()))
+            .collect();
+        self.state().answers = Some(answers);
+
+        self.run(operation)
+    }
+
+    fn run(&mut self, operation: Operation) -> Result<Progress, PamError> {
+        self.state().pending.clear();
+
+        let result = match operation {
+            Operation::Authenticate(flags) => self.pamh.authenticate(flags),
+            Operation::Chauthtok(flags) => self.pamh.chauthtok(flags),
+        };
+
+        let progress = self.state().progress(result);
+        self.operation = match progress {
+            Ok(Progress::Pending(_)) => Some(operation),
+            _ => None,
+        };
+
+        progress
+    }
+
+    fn state(&mut self) -> &mut State {
+        // libpam only uses the state during the calls made through `self.pamh`
+        unsafe { &mut *self.state.0 }
+    }
+}
+
+/// The conversation function of a [`Resumable`] transaction.
+extern "C" fn converse(
+    num_msg: c_int,
+    msg: *mut *const pam_message,
+    resp: *mut *mut pam_response,
+    appdata_ptr: *mut c_void,
+) -> c_int {
+    if msg.is_null() || resp.is_null() || appdata_ptr.is_null() {
+        return PAM_CONV_ERR;
+    }
+
+    let count = match usize::try_from(num_msg) {
+        Ok(count) if count > 0 && num_msg <= PAM_MAX_NUM_MSG => count,
+        _ => return PAM_CONV_ERR,
+    };
+
+    unsafe {
+        *resp = ptr::null_mut();
+
+        let state = &mut *appdata_ptr.cast::<State>();
+        let mut messages = Vec::with_capacity(count);
+        for message in Messages::new(num_msg, msg) {
+            let Some(message) = message.filter(|message| !message.msg.is_null()) else {
+                return PAM_CONV_ERR;
+            };
+
+            let style = message.msg_style;
+            if !is_prompt(style) && style != PAM_ERROR_MSG && style != PAM_TEXT_INFO {
+                return PAM_CONV_ERR;
+            }
+
+            messages.push(Message {
+                style,
+                text: CStr::from_ptr(message.msg).to_owned(),
+            });
+        }
+
+        let Ok(mut responses) = Responses::new(count) else {
+            return PAM_BUF_ERR;
+        };
+
+        if messages.iter().any(Message::is_prompt) {
+            let Some(answers) = state.answers.take() else {
+                // Wait for the answers, which are passed when the call is resumed
+                state.pending = messages;
+                return PAM_CONV_AGAIN;
+            };
+
+            let prompts = messages.iter().enumerate().filter(|(_, m)| m.is_prompt());
+            if prompts.clone().count() != answers.len() {
+                return PAM_CONV_ERR;
+            }
+
+            for ((i, _), answer) in prompts.zip(&answers) {
+                if answer.len() >= PAM_MAX_RESP_SIZE as usize {
+                    return PAM_CONV_ERR;
+                }
+                if responses.set(i, answer).is_err() {
+                    return PAM_BUF_ERR;
+                }
+            }
+        } else {
+            state.shown.append(&mut messages);
+        }
+
+        *resp = responses.into_raw();
+    }
+
+    PAM_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::free_responses;
+
+    /// Call the conversation with `messages` like a module would, and copy the responses.
+    fn call(state: &mut State, messages: &[(c_int, &CStr)]) -> (c_int, Vec<Option<Vec<u8>>>) {
+        let messages: Vec<pam_message> = messages
+            .iter()
+            .map(|&(msg_style, msg)| pam_message {
+                msg_style,
+                msg: msg.as_ptr() as _,
+            })
+            .collect();
+        let mut pointers: Vec<*const pam_message> = messages.iter().map(|m| m as _).collect();
+        let mut resp = ptr::null_mut();
+
+        let status = converse(
+            pointers.len() as c_int,
+            pointers.as_mut_ptr(),
+            &mut resp,
+            (state as *mut State).cast(),
+        );
+        if resp.is_null() {
+            return (status, Vec::new());
+        }
+
+        unsafe {
+            let responses = (0..messages.len())
+                .map(|i| {
+                    let response = &*resp.add(i);
+                    (!response.resp.is_null())
+                        .then(|| CStr::from_ptr(response.resp).to_bytes().to_vec())
+                })
+                .collect();
+            free_responses(resp, messages.len());
+            (status, responses)
+        }
+    }
+
+    fn answers(answers: &[&[u8]]) -> Option<Vec<Secret>> {
+        Some(answers.iter().map(|answer| Secret::new(answer)).collect())
+    }
+
+    #[test]
+    fn missing_answers() {
+        let mut state = State::default();
+        let (status, responses) = call(&mut state, &[(PAM_PROMPT_ECHO_OFF, c"Password: ")]);
+
+        assert_eq!(status, PAM_CONV_AGAIN);
+        assert!(responses.is_empty());
+        assert_eq!(
+            state.pending,
+            [Message {
+                style: PAM_PROMPT_ECHO_OFF,
+                text: c"Password: ".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn answers_for_prompts() {
+        let messages = [
+            (PAM_PROMPT_ECHO_ON, c"login: "),
+            (PAM_TEXT_INFO, c"Welcome"),
+            (PAM_PROMPT_ECHO_OFF, c"Password: "),
+        ];
+
+        // The informational message does not get an answer
+        let mut state = State {
+            answers: answers(&[b"user", b"hunter2"]),
+            ..State::default()
+        };
+        let (status, responses) = call(&mut state, &messages);
+        assert_eq!(status, PAM_SUCCESS);
+        assert_eq!(
+            responses,
+            [Some(b"user".to_vec()), None, Some(b"hunter2".to_vec())]
+        );
+        assert!(state.answers.is_none());
+
+        for wrong in [&[b"user".as_slice()][..], &[b"user", b"hunter2", b"extra"]] {
+            let mut state = State {
+                answers: answers(wrong),
+                ..State::default()
+            };
+            assert_eq!(call(&mut state, &messages).0, PAM_CONV_ERR);
+        }
+    }
+
+    #[test]
+    fn long_answer() {
+        let long = vec![b'a'; PAM_MAX_RESP_SIZE as usize];
+        let mut state = State {
+            answers: answers(&[&long]),
+            ..State::default()
+        };
+        let (status, responses) = call(&mut state, &[(PAM_PROMPT_ECHO_OFF, c"Password: ")]);
+
+        assert_eq!(status, PAM_CONV_ERR);
+        assert!(responses.is_empty());
+
+        let mut state = State {
+            answers: answers(&[&long[1..]]),
+            ..State::default()
+        };
+        let (status, _) = call(&mut state, &[(PAM_PROMPT_ECHO_OFF, c"Password: ")]);
+        assert_eq!(status, PAM_SUCCESS);
+    }
+
+    #[test]
+    fn info_only() {
+        let mut state = State::default();
+        let (status, responses) = call(
+            &mut state,
+            &[(PAM_ERROR_MSG, c"Sorry"), (PAM_TEXT_INFO, c"Try again")],
+        );
+
+        assert_eq!(status, PAM_SUCCESS);
+        assert_eq!(responses, [None, None]);
+        assert!(state.pending.is_empty());
+        assert_eq!(
+            state.shown.iter().map(Message::text).collect::<Vec<_>>(),
+            [c"Sorry", c"Try again"]
+        );
+    }
+
+    /// A module that greets the user and asks for a password, and returns `PAM_INCOMPLETE` while
+    /// the conversation waits for it.
+    fn stub_module(state: &mut State) -> Result<(), PamError> {
+        state.pending.clear();
+
+        let (status, _) = call(state, &[(PAM_TEXT_INFO, c"Welcome")]);
+        assert_eq!(status, PAM_SUCCESS);
+
+        match call(state, &[(PAM_PROMPT_ECHO_OFF, c"Password: ")]) {
+            (PAM_CONV_AGAIN, _) => Err(PamError::Incomplete),
+            (PAM_SUCCESS, responses) if responses == [Some(b"hunter2".to_vec())] => Ok(()),
+            _ => Err(PamError::AuthErr),
+        }
+    }
+
+    #[test]
+    fn pending_then_done() {
+        let mut state = State::default();
+
+        let result = stub_module(&mut state);
+        let Ok(Progress::Pending(messages)) = state.progress(result) else {
+            panic!("The call is not pending");
+        };
+        let texts: Vec<_> = messages.iter().map(Message::text).collect();
+        assert_eq!(texts, [c"Welcome", c"Password: "]);
+
+        state.answers = answers(&[b"hunter2"]);
+        let result = stub_module(&mut state);
+        let Ok(Progress::Done(messages)) = state.progress(result) else {
+            panic!("The call did not finish");
+        };
+        assert_eq!(
+            messages.iter().map(Message::text).collect::<Vec<_>>(),
+            [c"Welcome"]
+        );
+
+        // A wrong answer fails the call
+        state.answers = answers(&[b"wrong"]);
+        let result = stub_module(&mut state);
+        assert_eq!(state.progress(result), Err(PamError::AuthErr));
+    }
+}
@@ -0,0 +1,106 @@
+//! Status codes that do not depend on the implementation.
+//!
+//! Linux-PAM and OpenPAM number most status codes differently. For example, `PAM_CONV_ERR` is 19
+//! in Linux-PAM and 6 in OpenPAM. [`PamStatus`] names a status independently of the numbering, and
+//! [`linux_pam::to_status`], [`linux_pam::from_status`], [`openpam::to_status`] and
+//! [`openpam::from_status`] translate it from and to the numbers of either implementation, no
+//! matter which one the crate is built for.
+//!
+//! Some statuses only exist in one implementation. Translating them into the other one returns
+//! `None`, so the caller picks a replacement instead of receiving a wrong code.
+//!
+//! ```
+//! use libpam_sys::status::PamStatus;
+//! use libpam_sys::{linux_pam, openpam};
+//!
+//! // A status received from a host that runs Linux-PAM
+//! let status = linux_pam::to_status(19);
+//! assert_eq!(status, Some(PamStatus::ConvErr));
+//! assert_eq!(openpam::from_status(PamStatus::ConvErr), Some(6));
+//!
+//! // OpenPAM has no `PAM_INCOMPLETE`
+//! assert_eq!(openpam::from_status(PamStatus::Incomplete), None);
+//! ```
+//!
+//! [`linux_pam::to_status`]: crate::linux_pam::to_status
+//! [`linux_pam::from_status`]: crate::linux_pam::from_status
+//! [`openpam::to_status`]: crate::openpam::to_status
+//! [`openpam::from_status`]: crate::openpam::from_status
+
+/// A status code of either implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PamStatus {
+    /// `PAM_SUCCESS`
+    Success,
+    /// `PAM_OPEN_ERR`
+    OpenErr,
+    /// `PAM_SYMBOL_ERR`
+    SymbolErr,
+    /// `PAM_SERVICE_ERR`
+    ServiceErr,
+    /// `PAM_SYSTEM_ERR`
+    SystemErr,
+    /// `PAM_BUF_ERR`
+    BufErr,
+    /// `PAM_PERM_DENIED`
+    PermDenied,
+    /// `PAM_AUTH_ERR`
+    AuthErr,
+    /// `PAM_CRED_INSUFFICIENT`
+    CredInsufficient,
+    /// `PAM_AUTHINFO_UNAVAIL`
+    AuthinfoUnavail,
+    /// `PAM_USER_UNKNOWN`
+    UserUnknown,
+    /// `PAM_MAXTRIES`
+    Maxtries,
+    /// `PAM_NEW_AUTHTOK_REQD`
+    NewAuthtokReqd,
+    /// `PAM_ACCT_EXPIRED`
+    AcctExpired,
+    /// `PAM_SESSION_ERR`
+    SessionErr,
+    /// `PAM_CRED_UNAVAIL`
+    CredUnavail,
+    /// `PAM_CRED_EXPIRED`
+    CredExpired,
+    /// `PAM_CRED_ERR`
+    CredErr,
+    /// `PAM_NO_MODULE_DATA`
+    NoModuleData,
+    /// `PAM_CONV_ERR`
+    ConvErr,
+    /// `PAM_AUTHTOK_ERR`
+    AuthtokErr,
+    /// `PAM_AUTHTOK_RECOVERY_ERR`
+    AuthtokRecoveryErr,
+    /// `PAM_AUTHTOK_LOCK_BUSY`
+    AuthtokLockBusy,
+    /// `PAM_AUTHTOK_DISABLE_AGING`
+    AuthtokDisableAging,
+    /// `PAM_TRY_AGAIN`
+    TryAgain,
+    /// `PAM_IGNORE`
+    Ignore,
+    /// `PAM_ABORT`
+    Abort,
+    /// `PAM_AUTHTOK_EXPIRED`
+    AuthtokExpired,
+    /// `PAM_MODULE_UNKNOWN`
+    ModuleUnknown,
+    /// `PAM_BAD_ITEM`
+    BadItem,
+    /// `PAM_CONV_AGAIN`, only in Linux-PAM
+    ConvAgain,
+    /// `PAM_INCOMPLETE`, only in Linux-PAM
+    Incomplete,
+    /// `PAM_DOMAIN_UNKNOWN`, only in OpenPAM
+    DomainUnknown,
+    /// `PAM_BAD_HANDLE`, only in OpenPAM
+    BadHandle,
+    /// `PAM_BAD_FEATURE`, only in OpenPAM
+    BadFeature,
+    /// `PAM_BAD_CONSTANT`, only in OpenPAM
+    BadConstant,
+}
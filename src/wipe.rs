@@ -0,0 +1,10 @@
+//! Overwriting secrets in memory, shared by the responses and the X authentication data.
+
+use std::ptr;
+
+/// Overwrite `bytes` with zeros in a way that is not optimized away.
+pub fn wipe(bytes: &mut [u8]) {
+    for byte in bytes {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
@@ -0,0 +1,145 @@
+//! The X server authentication data of the `PAM_XAUTHDATA` item.
+//!
+//! A display manager passes the cookie of the X server to modules by setting `PAM_XAUTHDATA`,
+//! before it opens the session. [`XAuth`] owns the name and data of such a cookie and converts
+//! from and to [`pam_xauth_data`].
+//!
+//! ```no_run
+//! use libpam_sys::pam_handle_t;
+//! use libpam_sys::xauth::XAuth;
+//!
+//! # let pamh: *mut pam_handle_t = std::ptr::null_mut();
+//! # let cookie = [0u8; 16];
+//! // In the display manager
+//! let xauth = XAuth::new(c"MIT-MAGIC-COOKIE-1", &cookie);
+//! unsafe { xauth.set(pamh)? };
+//!
+//! // In a module
+//! if let Some(xauth) = unsafe { XAuth::get(pamh)? } {
+//!     assert_eq!(xauth.name(), c"MIT-MAGIC-COOKIE-1");
+//! }
+//! # Ok::<(), libpam_sys::error::PamError>(())
+//! ```
+
+use std::ffi::{c_int, CStr, CString};
+use std::fmt;
+
+use crate::error::PamError;
+use crate::item::{self, get_item, set_item};
+use crate::linux_pam::pam_xauth_data;
+use crate::pam_handle_t;
+use crate::wipe::wipe;
+
+/// X server authentication data, such as an `MIT-MAGIC-COOKIE-1`.
+///
+/// The data is overwritten with zeros when this is dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct XAuth {
+    name: CString,
+    data: Vec<u8>,
+}
+
+impl XAuth {
+    /// Copy the authentication `name` and the cookie `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the name or the data is longer than `c_int::MAX` bytes.
+    pub fn new(name: &CStr, data: &[u8]) -> Self {
+        assert!(
+            c_int::try_from(name.to_bytes().len()).is_ok(),
+            "The name is too long"
+        );
+        assert!(c_int::try_from(data.len()).is_ok(), "The data is too long");
+
+        Self {
+            name: name.to_owned(),
+            data: data.to_vec(),
+        }
+    }
+
+    /// The name of the authentication method
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// The authentication data
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// A [`pam_xauth_data`] that points into `self`, for passing it to libpam.
+    ///
+    /// libpam copies the name and data when the item is set, so the result only has to be valid
+    /// during the call.
+    pub fn as_raw(&self) -> pam_xauth_data {
+        pam_xauth_data {
+            // The lengths were checked in `new`
+            namelen: self.name.to_bytes().len() as c_int,
+            name: self.name.as_ptr().cast_mut(),
+            datalen: self.data.len() as c_int,
+            data: self.data.as_ptr().cast_mut().cast(),
+        }
+    }
+
+    /// Copy the name and data of a [`pam_xauth_data`].
+    ///
+    /// Returns `None` when it has no name.
+    ///
+    /// # Safety
+    ///
+    /// `name` has to be null or a nul-terminated string, and `data` has to be null or point to
+    /// `datalen` bytes.
+    pub unsafe fn from_raw(raw: &pam_xauth_data) -> Option<Self> {
+        if raw.name.is_null() {
+            return None;
+        }
+
+        let data = match usize::try_from(raw.datalen) {
+            Ok(len) if !raw.data.is_null() => std::slice::from_raw_parts(raw.data.cast(), len),
+            _ => &[],
+        };
+
+        Some(Self {
+            name: CStr::from_ptr(raw.name).to_owned(),
+            data: data.to_vec(),
+        })
+    }
+
+    /// Set the `PAM_XAUTHDATA` item of `pamh` to a copy of `self`.
+    ///
+    /// Returns the error of `pam_set_item` when it fails.
+    ///
+    /// # Safety
+    ///
+    /// `pamh` has to be a valid handle.
+    pub unsafe fn set(&self, pamh: *mut pam_handle_t) -> Result<(), PamError> {
+        set_item::<item::XAuthData>(pamh, Some(&self.as_raw()))
+    }
+
+    /// Copy the `PAM_XAUTHDATA` item of `pamh`, which is how a module receives the data.
+    ///
+    /// Returns `Ok(None)` when the item is not set, and the error of `pam_get_item` when it
+    /// fails.
+    ///
+    /// # Safety
+    ///
+    /// `pamh` has to be a valid handle.
+    pub unsafe fn get(pamh: *const pam_handle_t) -> Result<Option<Self>, PamError> {
+        Ok(get_item::<item::XAuthData>(pamh)?.and_then(|raw| Self::from_raw(raw)))
+    }
+}
+
+impl fmt::Debug for XAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XAuth")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for XAuth {
+    fn drop(&mut self) {
+        wipe(&mut self.data);
+    }
+}
@@ -0,0 +1,234 @@
+//! Reads the symbols of generated libraries and splits library paths like the build script.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+#[allow(dead_code)]
+#[path = "../build/link.rs"]
+mod link;
+#[path = "../build/symbols.rs"]
+mod symbols;
+
+use link::LinkConfig;
+
+/// Writes the fields of an ELF file in its byte order.
+struct Writer {
+    bytes: Vec<u8>,
+    big_endian: bool,
+}
+
+impl Writer {
+    fn put(&mut self, offset: usize, value: &[u8]) {
+        if self.bytes.len() < offset + value.len() {
+            self.bytes.resize(offset + value.len(), 0);
+        }
+        self.bytes[offset..offset + value.len()].copy_from_slice(value);
+    }
+
+    fn u16(&mut self, offset: usize, value: u16) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+        self.put(offset, &bytes);
+    }
+
+    fn u32(&mut self, offset: usize, value: u32) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+        self.put(offset, &bytes);
+    }
+
+    /// A word of the ELF class, which is 4 or 8 bytes
+    fn word(&mut self, offset: usize, value: u64, is_64: bool) {
+        if is_64 {
+            let bytes = if self.big_endian {
+                value.to_be_bytes()
+            } else {
+                value.to_le_bytes()
+            };
+            self.put(offset, &bytes);
+        } else {
+            self.u32(offset, value as u32);
+        }
+    }
+}
+
+const STRINGS: &[u8] = b"\0pam_start\0malloc\0";
+const STRINGS_OFFSET: usize = 0x40;
+const SYMBOLS_OFFSET: usize = 0x60;
+const SECTIONS_OFFSET: usize = 0x100;
+
+/// A shared object whose `.dynsym` defines `pam_start` and references the undefined `malloc`.
+fn elf(is_64: bool, big_endian: bool) -> Vec<u8> {
+    let mut elf = Writer {
+        bytes: Vec::new(),
+        big_endian,
+    };
+
+    elf.put(0, b"\x7fELF");
+    elf.put(
+        4,
+        &[if is_64 { 2 } else { 1 }, if big_endian { 2 } else { 1 }, 1],
+    );
+    // ET_DYN
+    elf.u16(0x10, 3);
+    elf.put(STRINGS_OFFSET, STRINGS);
+
+    let (symbol_size, section_size) = if is_64 { (24, 64) } else { (16, 40) };
+    if is_64 {
+        elf.word(0x28, SECTIONS_OFFSET as u64, true);
+        elf.u16(0x3a, section_size);
+        elf.u16(0x3c, 3);
+    } else {
+        elf.word(0x20, SECTIONS_OFFSET as u64, false);
+        elf.u16(0x2e, section_size);
+        elf.u16(0x30, 3);
+    }
+
+    // The null symbol, `pam_start` in section 1 and the undefined `malloc`
+    for (index, (name, shndx)) in [(0, 0), (1, 1), (11, 0)].into_iter().enumerate() {
+        let base = SYMBOLS_OFFSET + index * symbol_size;
+        elf.u32(base, name);
+        elf.u16(if is_64 { base + 6 } else { base + 14 }, shndx);
+    }
+
+    // The null section, `.dynsym` and `.dynstr`
+    let sections = [
+        (11, SYMBOLS_OFFSET, 3 * symbol_size, 2, symbol_size),
+        (3, STRINGS_OFFSET, STRINGS.len(), 0, 0),
+    ];
+    for (index, (kind, offset, size, link, entsize)) in sections.into_iter().enumerate() {
+        let base = SECTIONS_OFFSET + (index + 1) * section_size as usize;
+        elf.u32(base + 4, kind);
+        if is_64 {
+            elf.word(base + 24, offset as u64, true);
+            elf.word(base + 32, size as u64, true);
+            elf.u32(base + 40, link);
+            elf.word(base + 56, entsize as u64, true);
+        } else {
+            elf.word(base + 16, offset as u64, false);
+            elf.word(base + 20, size as u64, false);
+            elf.u32(base + 24, link);
+            elf.word(base + 36, entsize as u64, false);
+        }
+    }
+
+    elf.bytes
+}
+
+/// A static archive whose symbol table has the System V layout with `name` and `width`.
+fn archive(name: &str, width: usize, symbols: &[&str]) -> Vec<u8> {
+    let mut table = Vec::new();
+    let count = symbols.len() as u64;
+    table.extend_from_slice(&count.to_be_bytes()[8 - width..]);
+    // The offsets of the members that define the symbols
+    table.resize(table.len() + symbols.len() * width, 0);
+    for symbol in symbols {
+        table.extend_from_slice(symbol.as_bytes());
+        table.push(0);
+    }
+
+    let mut archive = b"!<arch>\n".to_vec();
+    let header = format!(
+        "{name:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+        0,
+        0,
+        0,
+        0,
+        table.len()
+    );
+    assert_eq!(header.len(), 60);
+    archive.extend_from_slice(header.as_bytes());
+    archive.extend_from_slice(&table);
+    archive
+}
+
+/// Write `data` to a file and read its symbols.
+fn read(name: &str, data: &[u8]) -> std::io::Result<HashSet<String>> {
+    let path = env::temp_dir().join(format!("libpam-sys-{name}-{}", std::process::id()));
+    fs::write(&path, data).unwrap();
+    let symbols = symbols::read(&path);
+    fs::remove_file(path).unwrap();
+    symbols
+}
+
+#[test]
+fn elf_classes_and_byte_orders() {
+    for is_64 in [false, true] {
+        for big_endian in [false, true] {
+            let symbols = read(
+                &format!("elf-{is_64}-{big_endian}"),
+                &elf(is_64, big_endian),
+            );
+            // `malloc` is undefined, so it is not provided by the library
+            assert_eq!(
+                symbols.unwrap(),
+                HashSet::from(["pam_start".to_string()]),
+                "64-bit: {is_64}, big-endian: {big_endian}"
+            );
+        }
+    }
+}
+
+#[test]
+fn archives() {
+    for (name, width) in [("/", 4), ("/SYM64/", 8)] {
+        let symbols = read("archive", &archive(name, width, &["pam_start", "pam_end"]));
+        assert_eq!(
+            symbols.unwrap(),
+            HashSet::from(["pam_start".to_string(), "pam_end".to_string()]),
+            "{name}"
+        );
+    }
+
+    // GNU archives without a symbol table start with another member
+    assert!(read("archive-no-table", &archive("pam_start.o/", 4, &[])).is_err());
+}
+
+#[test]
+fn invalid() {
+    assert!(read("empty", b"").is_err());
+    assert!(read("text", b"INPUT(-lpam)").is_err());
+
+    let mut truncated = elf(true, false);
+    truncated.truncate(SECTIONS_OFFSET + 10);
+    assert!(read("truncated", &truncated).is_err());
+
+    assert!(symbols::read(Path::new("/nonexistent/libpam.so")).is_err());
+}
+
+#[test]
+fn system_library() {
+    let path = Path::new("/usr/lib/x86_64-linux-gnu/libpam.so.0");
+    if !path.exists() {
+        return;
+    }
+
+    let symbols = symbols::read(path).unwrap();
+    assert!(symbols.contains("pam_start"));
+    assert!(!symbols.contains("malloc"));
+}
+
+#[test]
+fn from_path() {
+    let cases = [
+        ("/usr/lib/libpam.so", Some("/usr/lib"), "pam", false),
+        ("/usr/lib/libpam.so.0", Some("/usr/lib"), "pam", false),
+        ("/opt/pam/lib/libpam.a", Some("/opt/pam/lib"), "pam", true),
+        ("libpam.so", None, "pam", false),
+        ("pam", None, "pam", false),
+    ];
+
+    for (path, dir, name, statik) in cases {
+        let config = LinkConfig::from_path(Path::new(path));
+        assert_eq!(config.dir, dir.map(PathBuf::from), "{path}");
+        assert_eq!(config.name, name, "{path}");
+        assert_eq!(config.statik, statik, "{path}");
+    }
+}
@@ -0,0 +1,77 @@
+//! Resolves libraries and symbols that do not exist with the `dlopen` feature.
+#![cfg(feature = "dlopen")]
+
+use std::ffi::{c_char, c_int};
+use std::panic;
+
+use libpam_sys::dlopen::{self, LoadError, MissingSymbol, SharedLibrary, Symbol, LIBPAM};
+use libpam_sys::{PAM_OPEN_ERR, PAM_SYMBOL_ERR};
+
+static MISSING_LIBRARY: SharedLibrary =
+    SharedLibrary::new("libmissing", &["libpam-sys-missing.so.0"]);
+
+#[test]
+fn missing_library() {
+    let err = MISSING_LIBRARY.load().unwrap_err();
+    assert!(matches!(
+        err,
+        LoadError::Library {
+            name: "libmissing",
+            ..
+        }
+    ));
+    assert!(!MISSING_LIBRARY.is_available("pam_start"));
+
+    static SYMBOL: Symbol<unsafe extern "C" fn() -> c_int> =
+        Symbol::new(&MISSING_LIBRARY, "pam_start\0");
+    let err = SYMBOL.get().unwrap_err();
+    assert_eq!(c_int::missing(err), PAM_OPEN_ERR);
+    assert!(<*const c_char>::missing(err).is_null());
+}
+
+#[test]
+fn missing_symbol() {
+    if dlopen::load().is_err() {
+        return;
+    }
+    assert!(dlopen::is_available("pam_start"));
+    assert!(!dlopen::is_available("pam_libpam_sys_missing"));
+
+    static SYMBOL: Symbol<unsafe extern "C" fn() -> c_int> =
+        Symbol::new(&LIBPAM, "pam_libpam_sys_missing\0");
+    let err = SYMBOL.get().unwrap_err();
+    assert!(matches!(
+        err,
+        LoadError::Symbol {
+            name: "pam_libpam_sys_missing",
+            ..
+        }
+    ));
+    assert_eq!(c_int::missing(err), PAM_SYMBOL_ERR);
+    assert!(<*mut c_char>::missing(err).is_null());
+
+    // Without a return value or through `Deref`, there is nothing to report the error with
+    assert!(panic::catch_unwind(|| <()>::missing(err)).is_err());
+    assert!(panic::catch_unwind(|| *SYMBOL).is_err());
+}
+
+#[test]
+fn present_symbol() {
+    if dlopen::load().is_err() {
+        return;
+    }
+
+    static SYMBOL: Symbol<unsafe extern "C" fn()> = Symbol::new(&LIBPAM, "pam_getenvlist\0");
+    assert!(SYMBOL.get().is_ok());
+}
+
+#[cfg(feature = "misc")]
+#[test]
+fn static_mut_ptr() {
+    if dlopen::LIBPAM_MISC.load().is_err() {
+        return;
+    }
+
+    let warn_time = libpam_sys::static_mut_ptr!(libpam_sys::linux_pam::pam_misc_conv_warn_time);
+    assert!(!warn_time.is_null());
+}
@@ -0,0 +1,59 @@
+//! Converts status codes from and to `PamError`, and compares the messages with libpam.
+
+use std::ffi::c_int;
+
+use libpam_sys::error::PamError;
+use libpam_sys::{PAM_AUTH_ERR, PAM_SUCCESS, PAM_SYSTEM_ERR};
+
+/// Every status code of the implementation, and a few beyond it
+const CODES: std::ops::Range<c_int> = -1..64;
+
+#[test]
+fn round_trip() {
+    let errors: Vec<PamError> = CODES.filter_map(PamError::from_code).collect();
+    assert!(errors.contains(&PamError::AuthErr));
+
+    for err in errors {
+        assert_eq!(PamError::try_from(err.code()), Ok(err));
+        assert_eq!(c_int::from(err), err.code());
+        assert_eq!(PamError::check(err.code()), Err(err));
+    }
+}
+
+#[test]
+fn success() {
+    assert_eq!(PamError::from_code(PAM_SUCCESS), None);
+    assert_eq!(PamError::try_from(PAM_SUCCESS), Err(PAM_SUCCESS));
+    assert_eq!(PamError::check(PAM_SUCCESS), Ok(()));
+}
+
+#[test]
+fn unknown() {
+    assert_eq!(PamError::try_from(1000), Err(1000));
+    assert_eq!(PamError::check(1000), Err(PamError::SystemErr));
+    assert_eq!(PamError::SystemErr.code(), PAM_SYSTEM_ERR);
+}
+
+#[test]
+fn display() {
+    let err = PamError::try_from(PAM_AUTH_ERR).unwrap();
+    assert_eq!(err.to_string(), err.message());
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(err.to_string(), "Authentication failure");
+}
+
+/// The built-in messages are the untranslated ones of `pam_strerror`.
+#[cfg(pam_impl = "linux-pam")]
+#[test]
+fn strerror() {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use libpam_sys::pam_strerror;
+
+    for err in CODES.filter_map(PamError::from_code) {
+        let message = unsafe { CStr::from_ptr(pam_strerror(ptr::null_mut(), err.code())) };
+        assert_eq!(message.to_str(), Ok(err.message()), "{err:?}");
+    }
+}
@@ -0,0 +1,83 @@
+//! Calls the `PAM_FAIL_DELAY` function like libpam does after a failed authentication.
+#![cfg(all(feature = "conv", feature = "linux-pam"))]
+
+use std::cell::Cell;
+use std::ffi::{c_int, c_uint, c_void, CStr, CString};
+use std::rc::Rc;
+use std::time::Duration;
+
+use libpam_sys::conv::{self, Conversation, Result, WithFailDelay};
+use libpam_sys::PAM_AUTH_ERR;
+
+struct Silent;
+
+impl Conversation for Silent {
+    fn prompt_echo_on(&mut self, _msg: &CStr) -> Result<CString> {
+        Err("No input".into())
+    }
+
+    fn prompt_echo_off(&mut self, _msg: &CStr) -> Result<CString> {
+        Err("No input".into())
+    }
+
+    fn error_msg(&mut self, _msg: &CStr) -> Result<()> {
+        Ok(())
+    }
+
+    fn text_info(&mut self, _msg: &CStr) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Call the `PAM_FAIL_DELAY` function of `conversation` with its `appdata_ptr`, like libpam.
+fn fail_delay<C: Conversation>(conversation: &mut C, status: c_int, usec_delay: c_uint) {
+    unsafe {
+        let pam_conv = conv::to_pam_conv(conversation);
+        let function = conv::fail_delay_fn::<C>().unwrap();
+        function(status, usec_delay, pam_conv.app_dataptr);
+    }
+}
+
+#[test]
+fn closure() {
+    let mut delays = Vec::new();
+    let mut conversation = WithFailDelay::new(Silent, |status, delay| delays.push((status, delay)));
+
+    fail_delay(&mut conversation, PAM_AUTH_ERR, 2_000_000);
+    fail_delay(&mut conversation, PAM_AUTH_ERR, 1500);
+
+    assert_eq!(
+        delays,
+        [
+            (PAM_AUTH_ERR, Duration::from_secs(2)),
+            (PAM_AUTH_ERR, Duration::from_micros(1500)),
+        ]
+    );
+}
+
+/// The conversation of `PamHandle::start_with` is a `Box<dyn Conversation>`.
+#[test]
+fn boxed() {
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+    let conversation = WithFailDelay::new(Silent, move |_, _| counter.set(counter.get() + 1));
+
+    let mut conversation: Box<dyn Conversation> = Box::new(conversation);
+    fail_delay(&mut conversation, PAM_AUTH_ERR, 0);
+
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn panic() {
+    let mut conversation = WithFailDelay::new(Silent, |_, _| panic!("No timer"));
+    fail_delay(&mut conversation, PAM_AUTH_ERR, 0);
+}
+
+#[test]
+fn no_appdata() {
+    unsafe {
+        let function = conv::fail_delay_fn::<Silent>().unwrap();
+        function(PAM_AUTH_ERR, 0, std::ptr::null_mut::<c_void>());
+    }
+}
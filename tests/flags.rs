@@ -0,0 +1,83 @@
+//! Builds the flags of every call and compares them with the constants of the implementation.
+#![cfg(feature = "flags")]
+
+use std::ffi::c_int;
+
+use libpam_sys::flags::{AuthFlags, ChauthtokFlags, CredFlags, SessionFlags};
+
+#[test]
+fn bits() {
+    assert_eq!(AuthFlags::empty().bits(), 0);
+    assert_eq!(
+        c_int::from(AuthFlags::SILENT | AuthFlags::DISALLOW_NULL_AUTHTOK),
+        libpam_sys::PAM_SILENT | libpam_sys::PAM_DISALLOW_NULL_AUTHTOK
+    );
+    assert_eq!(
+        c_int::from(CredFlags::SILENT | CredFlags::REFRESH_CRED),
+        libpam_sys::PAM_SILENT | libpam_sys::PAM_REFRESH_CRED
+    );
+    assert_eq!(
+        c_int::from(ChauthtokFlags::CHANGE_EXPIRED_AUTHTOK),
+        libpam_sys::PAM_CHANGE_EXPIRED_AUTHTOK
+    );
+    assert_eq!(c_int::from(SessionFlags::SILENT), libpam_sys::PAM_SILENT);
+}
+
+#[cfg(pam_impl = "linux-pam")]
+#[test]
+fn linux_pam() {
+    assert_eq!(CredFlags::ESTABLISH_CRED.bits(), 0x2);
+    assert_eq!(SessionFlags::SILENT.bits(), 0x8000);
+    assert_eq!(ChauthtokFlags::PRELIM_CHECK.bits(), 0x4000);
+}
+
+#[cfg(pam_impl = "openpam")]
+#[test]
+fn openpam() {
+    assert_eq!(CredFlags::ESTABLISH_CRED.bits(), 0x1);
+    assert_eq!(SessionFlags::SILENT.bits(), c_int::MIN);
+    assert_eq!(ChauthtokFlags::PRELIM_CHECK.bits(), 0x1);
+}
+
+/// Flags of other calls are rejected.
+#[test]
+fn foreign_flags() {
+    let refresh = libpam_sys::PAM_REFRESH_CRED;
+    assert_eq!(SessionFlags::from_bits(refresh), None);
+    assert_eq!(CredFlags::from_bits(refresh), Some(CredFlags::REFRESH_CRED));
+
+    let change_expired = libpam_sys::PAM_CHANGE_EXPIRED_AUTHTOK;
+    assert_eq!(AuthFlags::from_bits(change_expired), None);
+    assert_eq!(
+        ChauthtokFlags::from_bits(change_expired),
+        Some(ChauthtokFlags::CHANGE_EXPIRED_AUTHTOK)
+    );
+}
+
+#[test]
+fn cred_actions() {
+    assert!(CredFlags::empty().is_valid());
+    assert!(CredFlags::SILENT.is_valid());
+
+    for action in CredFlags::ACTIONS.iter() {
+        assert!(action.is_valid(), "{action:?}");
+        assert!((action | CredFlags::SILENT).is_valid(), "{action:?}");
+
+        for other in CredFlags::ACTIONS.iter().filter(|&other| other != action) {
+            assert!(!(action | other).is_valid(), "{action:?} | {other:?}");
+        }
+    }
+
+    assert!(!CredFlags::ACTIONS.is_valid());
+}
+
+#[test]
+fn chauthtok_module_only() {
+    assert!(ChauthtokFlags::empty().is_valid());
+    assert!((ChauthtokFlags::SILENT | ChauthtokFlags::CHANGE_EXPIRED_AUTHTOK).is_valid());
+
+    for flag in ChauthtokFlags::MODULE_ONLY.iter() {
+        assert!(!flag.is_valid(), "{flag:?}");
+        assert!(!(flag | ChauthtokFlags::SILENT).is_valid(), "{flag:?}");
+    }
+}
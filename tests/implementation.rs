@@ -0,0 +1,56 @@
+//! Compares the implementation and version resolved by the build with the loaded library.
+
+use libpam_sys::implementation::{PamImplementation, PamVersion};
+use libpam_sys::{IMPLEMENTATION, PAM_VERSION};
+
+#[test]
+fn implementation() {
+    let expected = if cfg!(pam_impl = "openpam") {
+        PamImplementation::OpenPam
+    } else {
+        PamImplementation::LinuxPam
+    };
+    assert_eq!(IMPLEMENTATION, expected);
+}
+
+#[cfg(any(feature = "libc", feature = "dlopen"))]
+#[test]
+fn detect() {
+    if let Some(detected) = libpam_sys::implementation::detect() {
+        assert_eq!(detected, IMPLEMENTATION);
+    }
+}
+
+/// The version is the one the build script reported.
+#[test]
+fn version() {
+    let reported = option_env!("LIBPAM_SYS_PAM_VERSION");
+    assert_eq!(PAM_VERSION, reported.and_then(PamVersion::parse));
+}
+
+#[test]
+fn parse() {
+    assert_eq!(PamVersion::parse("1.5.2"), Some(PamVersion::new(1, 5, 2)));
+    assert_eq!(PamVersion::parse("1.4"), Some(PamVersion::new(1, 4, 0)));
+    assert_eq!(
+        PamVersion::parse("20190224"),
+        Some(PamVersion::new(20190224, 0, 0))
+    );
+
+    for invalid in [
+        "",
+        ".",
+        "1.",
+        ".1",
+        "1..2",
+        "1.2.3.4",
+        "1.5.2~rc1",
+        "v1",
+        "99999999999",
+    ] {
+        assert_eq!(PamVersion::parse(invalid), None, "{invalid}");
+    }
+
+    assert!(PamVersion::new(1, 4, 0) < PamVersion::new(1, 5, 2));
+    assert_eq!(PamVersion::new(1, 5, 2).to_string(), "1.5.2");
+}
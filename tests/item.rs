@@ -0,0 +1,107 @@
+//! Sets items of a transaction and reads them back.
+#![cfg(all(feature = "handle", feature = "linux-pam"))]
+
+use std::ffi::{c_int, c_void, CString};
+use std::path::{Path, PathBuf};
+use std::{env, fs, ptr};
+
+use libpam_sys::handle::PamHandle;
+use libpam_sys::item::{Conv, RHost, Service, User, XAuthData};
+use libpam_sys::xauth::XAuth;
+use libpam_sys::{pam_conv, pam_message, pam_response, PAM_CONV_ERR};
+
+extern "C" fn conversation(
+    _num_msg: c_int,
+    _msg: *mut *const pam_message,
+    _resp: *mut *mut pam_response,
+    _appdata_ptr: *mut c_void,
+) -> c_int {
+    PAM_CONV_ERR
+}
+
+/// A configuration directory with an empty service, so no system configuration is read
+fn conf_dir(name: &str) -> PathBuf {
+    let conf_dir = env::temp_dir().join(format!("libpam-sys-{name}-{}", std::process::id()));
+    fs::create_dir_all(&conf_dir).unwrap();
+    fs::write(conf_dir.join("libpam-sys-test"), "").unwrap();
+    conf_dir
+}
+
+fn start(conf_dir: &Path, conv: &pam_conv) -> PamHandle {
+    let conf_dir = CString::new(conf_dir.to_str().unwrap()).unwrap();
+    PamHandle::start_confdir(c"libpam-sys-test", Some(c"nobody"), conv, &conf_dir).unwrap()
+}
+
+#[test]
+fn strings() {
+    let conf_dir = conf_dir("item-strings");
+    let conv = pam_conv {
+        conv: conversation,
+        app_dataptr: ptr::null_mut(),
+    };
+    let mut pamh = start(&conf_dir, &conv);
+
+    assert_eq!(pamh.get_item::<User>().unwrap(), Some(c"nobody"));
+    assert_eq!(pamh.get_item::<RHost>().unwrap(), None);
+
+    // libpam keeps a copy, so the value may be dropped
+    let rhost = CString::from(c"example.com");
+    pamh.set_item::<RHost>(Some(&rhost)).unwrap();
+    drop(rhost);
+    assert_eq!(pamh.get_item::<RHost>().unwrap(), Some(c"example.com"));
+
+    pamh.set_item::<User>(None).unwrap();
+    assert_eq!(pamh.get_item::<User>().unwrap(), None);
+
+    // The service cannot be unset, and Linux-PAM lowercases it
+    assert_eq!(pamh.get_item::<Service>().unwrap(), c"libpam-sys-test");
+    pamh.set_item::<Service>(c"Other").unwrap();
+    assert_eq!(pamh.get_item::<Service>().unwrap(), c"other");
+
+    pamh.end().unwrap();
+    fs::remove_dir_all(conf_dir).unwrap();
+}
+
+#[test]
+fn xauth_data() {
+    let conf_dir = conf_dir("item-xauth");
+    let conv = pam_conv {
+        conv: conversation,
+        app_dataptr: ptr::null_mut(),
+    };
+    let mut pamh = start(&conf_dir, &conv);
+
+    let xauth = XAuth::new(c"MIT-MAGIC-COOKIE-1", &[1, 0, 2, 3]);
+    pamh.set_xauth(&xauth).unwrap();
+    drop(xauth);
+
+    let raw = pamh.get_item::<XAuthData>().unwrap().unwrap();
+    let xauth = unsafe { XAuth::from_raw(raw) }.unwrap();
+    assert_eq!(xauth.name(), c"MIT-MAGIC-COOKIE-1");
+    assert_eq!(xauth.data(), [1, 0, 2, 3]);
+
+    pamh.end().unwrap();
+    fs::remove_dir_all(conf_dir).unwrap();
+}
+
+#[test]
+fn conv() {
+    let conf_dir = conf_dir("item-conv");
+    let conv = pam_conv {
+        conv: conversation,
+        app_dataptr: ptr::null_mut(),
+    };
+    let mut pamh = start(&conf_dir, &conv);
+
+    let other = pam_conv {
+        conv: conversation,
+        app_dataptr: ptr::dangling_mut::<u8>().cast(),
+    };
+    unsafe { pamh.set_item_unchecked::<Conv>(Some(&other)) }.unwrap();
+
+    let conv = pamh.get_item::<Conv>().unwrap().unwrap();
+    assert_eq!(conv.app_dataptr, other.app_dataptr);
+
+    pamh.end().unwrap();
+    fs::remove_dir_all(conf_dir).unwrap();
+}
@@ -0,0 +1,147 @@
+//! Reads the messages of fake callers that use either layout of the `msg` argument.
+
+use std::ffi::{c_int, CStr};
+use std::ptr;
+
+use libpam_sys::message::{MessageLayout, Messages};
+use libpam_sys::{pam_message, PAM_ERROR_MSG, PAM_PROMPT_ECHO_OFF, PAM_PROMPT_ECHO_ON};
+
+const MESSAGES: [(c_int, &CStr); 3] = [
+    (PAM_PROMPT_ECHO_ON, c"login: "),
+    (PAM_PROMPT_ECHO_OFF, c"Password: "),
+    (PAM_ERROR_MSG, c"Sorry"),
+];
+
+fn messages() -> Vec<pam_message> {
+    MESSAGES
+        .iter()
+        .map(|&(msg_style, msg)| pam_message {
+            msg_style,
+            msg: msg.as_ptr() as _,
+        })
+        .collect()
+}
+
+/// Read the messages like a conversation function would, and compare them with `MESSAGES`.
+fn assert_messages(num_msg: c_int, msg: *const *const pam_message, layout: MessageLayout) {
+    let messages = unsafe { Messages::with_layout(num_msg, msg, layout) };
+    assert_eq!(messages.len(), MESSAGES.len());
+
+    let received: Vec<(c_int, &CStr)> = messages
+        .map(Option::unwrap)
+        .map(|message| (message.msg_style, unsafe { CStr::from_ptr(message.msg) }))
+        .collect();
+    assert_eq!(received, MESSAGES);
+}
+
+/// A caller like Linux-PAM and OpenPAM, which passes an array of pointers to messages
+#[test]
+fn pointers() {
+    // Separate allocations, so reading them as one array would read garbage
+    let messages: Vec<Box<pam_message>> = messages().into_iter().map(Box::new).collect();
+    let pointers: Vec<*const pam_message> = messages.iter().map(|m| &**m as *const _).collect();
+
+    assert_messages(
+        pointers.len() as c_int,
+        pointers.as_ptr(),
+        MessageLayout::Pointers,
+    );
+}
+
+/// A caller like Solaris, which passes a pointer to an array of messages
+#[test]
+fn array() {
+    let messages = messages();
+    let array = messages.as_ptr();
+
+    assert_messages(messages.len() as c_int, &array, MessageLayout::Array);
+}
+
+/// The layouts only agree when there is a single message
+#[test]
+fn single_message() {
+    let message = messages().remove(0);
+    let pointer: *const pam_message = &message;
+
+    for layout in [MessageLayout::Pointers, MessageLayout::Array] {
+        let mut messages = unsafe { Messages::with_layout(1, &pointer, layout) };
+        assert!(ptr::eq(messages.next().unwrap().unwrap(), &message));
+        assert!(messages.next().is_none());
+    }
+}
+
+#[test]
+fn native_layout() {
+    assert_eq!(MessageLayout::NATIVE, MessageLayout::Pointers);
+}
+
+#[test]
+fn no_messages() {
+    assert_eq!(unsafe { Messages::new(-1, ptr::null()) }.len(), 0);
+    assert_eq!(unsafe { Messages::new(3, ptr::null()) }.len(), 0);
+}
+
+/// A null pointer in place of a message is yielded as `None` instead of being read
+#[test]
+fn null_message() {
+    let messages = messages();
+    let pointers = [&messages[0] as *const _, ptr::null(), &messages[2]];
+    let received: Vec<Option<c_int>> = unsafe { Messages::new(3, pointers.as_ptr()) }
+        .map(|message| message.map(|message| message.msg_style))
+        .collect();
+    assert_eq!(
+        received,
+        [Some(PAM_PROMPT_ECHO_ON), None, Some(PAM_ERROR_MSG)]
+    );
+
+    let array: *const pam_message = ptr::null();
+    let mut messages = unsafe { Messages::with_layout(2, &array, MessageLayout::Array) };
+    assert!(matches!(messages.next(), Some(None)));
+    assert!(matches!(messages.next(), Some(None)));
+    assert!(messages.next().is_none());
+}
+
+/// The conversation function of `to_pam_conv` fails on a null message instead of reading it
+#[cfg(feature = "conv")]
+#[test]
+fn null_message_conversation() {
+    use std::ffi::CString;
+
+    use libpam_sys::conv::{self, Conversation, Result};
+    use libpam_sys::{pam_response, PAM_CONV_ERR};
+
+    struct Answer;
+
+    impl Conversation for Answer {
+        fn prompt_echo_on(&mut self, _msg: &CStr) -> Result<CString> {
+            Ok(c"agent".into())
+        }
+
+        fn prompt_echo_off(&mut self, _msg: &CStr) -> Result<CString> {
+            Ok(c"secret".into())
+        }
+
+        fn error_msg(&mut self, _msg: &CStr) -> Result<()> {
+            Ok(())
+        }
+
+        fn text_info(&mut self, _msg: &CStr) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let messages = messages();
+    let mut pointers = [&messages[0] as *const _, ptr::null()];
+    let mut resp: *mut pam_response = ptr::null_mut();
+
+    let mut answer = Answer;
+    let pam_conv = unsafe { conv::to_pam_conv(&mut answer) };
+    let status = (pam_conv.conv)(
+        pointers.len() as c_int,
+        pointers.as_mut_ptr(),
+        &mut resp,
+        pam_conv.app_dataptr,
+    );
+    assert_eq!(status, PAM_CONV_ERR);
+    assert!(resp.is_null());
+}
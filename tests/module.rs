@@ -0,0 +1,54 @@
+//! Calls the entry points exported by `pam_module!` the way libpam does.
+
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+use std::sync::Mutex;
+
+use libpam_sys::module::PamServiceModule;
+use libpam_sys::{pam_handle_t, pam_module, PAM_IGNORE, PAM_SERVICE_ERR, PAM_SUCCESS};
+
+/// The arguments of the last call to `authenticate`
+static ARGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct TestModule;
+
+impl PamServiceModule for TestModule {
+    fn authenticate(_pamh: *mut pam_handle_t, _flags: c_int, args: &[&CStr]) -> c_int {
+        *ARGS.lock().unwrap() = args
+            .iter()
+            .map(|arg| arg.to_str().unwrap().to_string())
+            .collect();
+        PAM_SUCCESS
+    }
+
+    fn acct_mgmt(_pamh: *mut pam_handle_t, _flags: c_int, _args: &[&CStr]) -> c_int {
+        panic!("the hook panics");
+    }
+}
+
+pam_module!(TestModule);
+
+#[test]
+fn arguments() {
+    let mut argv: [*const c_char; 3] = [c"debug".as_ptr(), ptr::null(), c"nullok".as_ptr()];
+    let status = unsafe { pam_sm_authenticate(ptr::null_mut(), 0, 3, argv.as_mut_ptr()) };
+    assert_eq!(status, PAM_SUCCESS);
+    assert_eq!(*ARGS.lock().unwrap(), ["debug", "nullok"]);
+
+    // A null `argv` is no arguments, whatever `argc` says
+    let status = unsafe { pam_sm_authenticate(ptr::null_mut(), 0, 2, ptr::null_mut()) };
+    assert_eq!(status, PAM_SUCCESS);
+    assert!(ARGS.lock().unwrap().is_empty());
+}
+
+#[test]
+fn panic() {
+    let status = unsafe { pam_sm_acct_mgmt(ptr::null_mut(), 0, 0, ptr::null_mut()) };
+    assert_eq!(status, PAM_SERVICE_ERR);
+}
+
+#[test]
+fn default_hooks() {
+    let status = unsafe { pam_sm_open_session(ptr::null_mut(), 0, 0, ptr::null_mut()) };
+    assert_eq!(status, PAM_IGNORE);
+}
@@ -0,0 +1,49 @@
+//! Calls functions that were added in later Linux-PAM releases. These are linked when the library
+//! was recent enough at build time, and resolved at runtime otherwise.
+#![cfg(feature = "linux-pam")]
+
+use std::ffi::{c_int, c_void, CString};
+use std::{env, fs, ptr};
+
+use libpam_sys::linux_pam::pam_start_confdir;
+use libpam_sys::{
+    pam_conv, pam_end, pam_handle_t, pam_message, pam_response, PAM_CONV_ERR, PAM_SUCCESS,
+};
+
+extern "C" fn conversation(
+    _num_msg: c_int,
+    _msg: *mut *const pam_message,
+    _resp: *mut *mut pam_response,
+    _appdata_ptr: *mut c_void,
+) -> c_int {
+    PAM_CONV_ERR
+}
+
+#[test]
+fn start_confdir() {
+    // A configuration directory with an empty service
+    let conf_dir = env::temp_dir().join(format!("libpam-sys-confdir-{}", std::process::id()));
+    fs::create_dir_all(&conf_dir).unwrap();
+    fs::write(conf_dir.join("libpam-sys-test"), "").unwrap();
+    let conf_dir_c = CString::new(conf_dir.to_str().unwrap()).unwrap();
+
+    let conv = pam_conv {
+        conv: conversation,
+        app_dataptr: ptr::null_mut(),
+    };
+
+    let mut pamh: *mut pam_handle_t = ptr::null_mut();
+    let status = unsafe {
+        pam_start_confdir(
+            c"libpam-sys-test".as_ptr(),
+            c"nobody".as_ptr(),
+            &conv,
+            conf_dir_c.as_ptr(),
+            &mut pamh,
+        )
+    };
+    assert_eq!(status, PAM_SUCCESS);
+
+    unsafe { pam_end(pamh, PAM_SUCCESS) };
+    fs::remove_dir_all(conf_dir).unwrap();
+}
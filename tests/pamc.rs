@@ -0,0 +1,80 @@
+//! Builds and parses binary prompts, as bytes and as prompts allocated with `malloc`.
+#![cfg(feature = "pamc")]
+
+use std::ptr;
+
+use libpam_sys::linux_pam::{pamc_bp_t, PAM_BPC_DONE, PAM_BPC_SELECT};
+use libpam_sys::pamc::{free_raw, BinaryPrompt, PromptError};
+
+#[test]
+fn bytes_round_trip() {
+    let prompt = BinaryPrompt::new(PAM_BPC_SELECT as u8, b"agent/data\0with nul".to_vec()).unwrap();
+    let bytes = prompt.to_bytes();
+    assert_eq!(bytes.len(), prompt.size());
+    assert_eq!(&bytes[..5], &[0, 0, 0, 24, PAM_BPC_SELECT as u8]);
+
+    let parsed = BinaryPrompt::from_bytes(&bytes).unwrap();
+    assert_eq!(parsed, prompt);
+    assert_eq!(parsed.data(), b"agent/data\0with nul");
+}
+
+#[test]
+fn empty() {
+    let prompt = BinaryPrompt::new(PAM_BPC_DONE as u8, Vec::new()).unwrap();
+    assert_eq!(prompt.to_bytes(), [0, 0, 0, 5, PAM_BPC_DONE as u8]);
+    assert_eq!(BinaryPrompt::from_bytes(&prompt.to_bytes()), Ok(prompt));
+}
+
+#[test]
+fn raw_round_trip() {
+    let prompt = BinaryPrompt::new(PAM_BPC_SELECT as u8, vec![0xff; 300]).unwrap();
+    let raw = prompt.to_raw();
+    assert!(!raw.is_null());
+
+    assert_eq!(unsafe { BinaryPrompt::from_raw(raw) }, Ok(prompt));
+    unsafe { free_raw(raw) };
+}
+
+#[test]
+fn truncated_bytes() {
+    assert_eq!(
+        BinaryPrompt::from_bytes(&[0, 0, 0]),
+        Err(PromptError::TooShort { size: 3 })
+    );
+
+    // The header claims more bytes than there are
+    assert_eq!(
+        BinaryPrompt::from_bytes(&[0, 0, 0, 9, 1, b'a', b'b']),
+        Err(PromptError::SizeMismatch {
+            header: 9,
+            actual: 7
+        })
+    );
+
+    // And fewer
+    assert_eq!(
+        BinaryPrompt::from_bytes(&[0, 0, 0, 6, 1, b'a', b'b']),
+        Err(PromptError::SizeMismatch {
+            header: 6,
+            actual: 7
+        })
+    );
+}
+
+#[test]
+fn truncated_raw() {
+    assert_eq!(
+        unsafe { BinaryPrompt::from_raw(ptr::null_mut()) },
+        Err(PromptError::Null)
+    );
+
+    // A header with a size below its own size is rejected before reading the control byte
+    let mut bytes = [0u8, 0, 0, 3, 1];
+    let raw: pamc_bp_t = bytes.as_mut_ptr().cast();
+    assert_eq!(
+        unsafe { BinaryPrompt::from_raw(raw) },
+        Err(PromptError::TooShort { size: 3 })
+    );
+
+    unsafe { free_raw(ptr::null_mut()) };
+}
@@ -0,0 +1,98 @@
+//! Builds response arrays like a conversation function would, and releases them like libpam.
+#![cfg(feature = "libc")]
+
+use std::ffi::CStr;
+
+use libpam_sys::response::{free_responses, wipe, wipe_c_str, Responses, Secret};
+
+#[test]
+fn into_raw() {
+    let mut responses = Responses::new(3).unwrap();
+    responses.set(0, b"user").unwrap();
+    responses.set(2, b"first").unwrap();
+    responses.set(2, b"hunter2").unwrap();
+    responses.set_retcode(2, 7);
+
+    let raw = responses.into_raw();
+    unsafe {
+        let received: Vec<_> = (0..3)
+            .map(|i| {
+                let response = &*raw.add(i);
+                let resp = (!response.resp.is_null()).then(|| CStr::from_ptr(response.resp));
+                (resp, response.resp_retcode)
+            })
+            .collect();
+        assert_eq!(
+            received,
+            [(Some(c"user"), 0), (None, 0), (Some(c"hunter2"), 7)]
+        );
+
+        free_responses(raw, 3);
+    }
+}
+
+/// Dropping a partially built array releases it.
+#[test]
+fn drop_partial() {
+    let mut responses = Responses::new(2).unwrap();
+    responses.set(0, b"secret").unwrap();
+    drop(responses);
+}
+
+#[test]
+fn no_responses() {
+    let responses = Responses::new(0).unwrap();
+    assert!(responses.is_empty());
+    unsafe { free_responses(responses.into_raw(), 0) };
+}
+
+#[test]
+#[should_panic]
+fn out_of_bounds() {
+    let mut responses = Responses::new(1).unwrap();
+    let _ = responses.set(1, b"");
+}
+
+#[test]
+fn secret() {
+    let secret = Secret::new(b"hunter2");
+    assert_eq!(&*secret, b"hunter2");
+    assert_eq!(format!("{secret:?}"), "Secret(7 bytes)");
+
+    let mut bytes = *b"hunter2";
+    wipe(&mut bytes);
+    assert_eq!(bytes, [0; 7]);
+}
+
+/// `free_responses` only knows where a response ends from its first nul byte.
+#[test]
+fn wipe_up_to_nul() {
+    let mut bytes = *b"ab\0cd\0";
+    unsafe { wipe_c_str(bytes.as_mut_ptr().cast()) };
+    assert_eq!(bytes, *b"\0\0\0cd\0");
+}
+
+/// A binary response with nul bytes is wiped completely when its length is known.
+#[test]
+fn wipe_binary_response() {
+    let mut responses = Responses::new(1).unwrap();
+    responses.set(0, b"ab\0cd").unwrap();
+
+    let raw = responses.into_raw();
+    unsafe {
+        let resp = std::slice::from_raw_parts_mut((*raw).resp.cast::<u8>(), 5);
+        assert_eq!(resp, b"ab\0cd");
+        wipe(resp);
+        assert_eq!(resp, [0; 5]);
+
+        free_responses(raw, 1);
+    }
+}
+
+#[test]
+fn secret_spare_capacity() {
+    let mut bytes = Vec::with_capacity(16);
+    bytes.extend_from_slice(b"hunter2hunter2");
+    bytes.truncate(7);
+    drop(Secret::from(bytes));
+}
@@ -0,0 +1,169 @@
+//! Translates every status code between Linux-PAM, OpenPAM and `PamStatus`.
+
+use std::collections::HashSet;
+use std::ffi::c_int;
+
+use libpam_sys::status::PamStatus;
+use libpam_sys::{linux_pam, openpam};
+
+const ALL: [PamStatus; 36] = [
+    PamStatus::Success,
+    PamStatus::OpenErr,
+    PamStatus::SymbolErr,
+    PamStatus::ServiceErr,
+    PamStatus::SystemErr,
+    PamStatus::BufErr,
+    PamStatus::PermDenied,
+    PamStatus::AuthErr,
+    PamStatus::CredInsufficient,
+    PamStatus::AuthinfoUnavail,
+    PamStatus::UserUnknown,
+    PamStatus::Maxtries,
+    PamStatus::NewAuthtokReqd,
+    PamStatus::AcctExpired,
+    PamStatus::SessionErr,
+    PamStatus::CredUnavail,
+    PamStatus::CredExpired,
+    PamStatus::CredErr,
+    PamStatus::NoModuleData,
+    PamStatus::ConvErr,
+    PamStatus::AuthtokErr,
+    PamStatus::AuthtokRecoveryErr,
+    PamStatus::AuthtokLockBusy,
+    PamStatus::AuthtokDisableAging,
+    PamStatus::TryAgain,
+    PamStatus::Ignore,
+    PamStatus::Abort,
+    PamStatus::AuthtokExpired,
+    PamStatus::ModuleUnknown,
+    PamStatus::BadItem,
+    PamStatus::ConvAgain,
+    PamStatus::Incomplete,
+    PamStatus::DomainUnknown,
+    PamStatus::BadHandle,
+    PamStatus::BadFeature,
+    PamStatus::BadConstant,
+];
+
+const LINUX_PAM_ONLY: [PamStatus; 2] = [PamStatus::ConvAgain, PamStatus::Incomplete];
+
+const OPENPAM_ONLY: [PamStatus; 4] = [
+    PamStatus::DomainUnknown,
+    PamStatus::BadHandle,
+    PamStatus::BadFeature,
+    PamStatus::BadConstant,
+];
+
+/// Asserts that `codes` are exactly the codes with a status, and that they round trip.
+fn assert_codes(
+    codes: std::ops::Range<c_int>,
+    to_status: fn(c_int) -> Option<PamStatus>,
+    from_status: fn(PamStatus) -> Option<c_int>,
+) -> HashSet<PamStatus> {
+    let mut statuses = HashSet::new();
+    for code in codes.clone() {
+        let status = to_status(code).unwrap_or_else(|| panic!("{code} has no status"));
+        assert_eq!(from_status(status), Some(code), "{status:?}");
+        assert!(statuses.insert(status), "{status:?} has two codes");
+    }
+
+    for code in [-1, codes.start - 1, codes.end, codes.end + 1, c_int::MAX] {
+        assert_eq!(to_status(code), None, "{code}");
+    }
+
+    statuses
+}
+
+#[test]
+fn linux_pam() {
+    let statuses = assert_codes(
+        linux_pam::PAM_SUCCESS..linux_pam::_PAM_RETURN_VALUES,
+        linux_pam::to_status,
+        linux_pam::from_status,
+    );
+
+    for status in ALL {
+        let only_openpam = OPENPAM_ONLY.contains(&status);
+        assert_eq!(statuses.contains(&status), !only_openpam, "{status:?}");
+        assert_eq!(linux_pam::from_status(status).is_none(), only_openpam);
+    }
+}
+
+#[test]
+fn openpam() {
+    let statuses = assert_codes(
+        openpam::PAM_SUCCESS..openpam::PAM_NUM_ERRORS,
+        openpam::to_status,
+        openpam::from_status,
+    );
+
+    for status in ALL {
+        let only_linux_pam = LINUX_PAM_ONLY.contains(&status);
+        assert_eq!(statuses.contains(&status), !only_linux_pam, "{status:?}");
+        assert_eq!(openpam::from_status(status).is_none(), only_linux_pam);
+    }
+}
+
+/// The constants of the same name translate into each other.
+#[test]
+fn between_implementations() {
+    macro_rules! same {
+        ($($code:ident),*) => {
+            [$((stringify!($code), linux_pam::$code, openpam::$code)),*]
+        };
+    }
+
+    let codes = same![
+        PAM_SUCCESS,
+        PAM_OPEN_ERR,
+        PAM_SYMBOL_ERR,
+        PAM_SERVICE_ERR,
+        PAM_SYSTEM_ERR,
+        PAM_BUF_ERR,
+        PAM_PERM_DENIED,
+        PAM_AUTH_ERR,
+        PAM_CRED_INSUFFICIENT,
+        PAM_AUTHINFO_UNAVAIL,
+        PAM_USER_UNKNOWN,
+        PAM_MAXTRIES,
+        PAM_NEW_AUTHTOK_REQD,
+        PAM_ACCT_EXPIRED,
+        PAM_SESSION_ERR,
+        PAM_CRED_UNAVAIL,
+        PAM_CRED_EXPIRED,
+        PAM_CRED_ERR,
+        PAM_NO_MODULE_DATA,
+        PAM_CONV_ERR,
+        PAM_AUTHTOK_ERR,
+        PAM_AUTHTOK_RECOVERY_ERR,
+        PAM_AUTHTOK_LOCK_BUSY,
+        PAM_AUTHTOK_DISABLE_AGING,
+        PAM_TRY_AGAIN,
+        PAM_IGNORE,
+        PAM_ABORT,
+        PAM_AUTHTOK_EXPIRED,
+        PAM_MODULE_UNKNOWN,
+        PAM_BAD_ITEM
+    ];
+    assert_eq!(
+        codes.len(),
+        ALL.len() - LINUX_PAM_ONLY.len() - OPENPAM_ONLY.len()
+    );
+
+    for (name, linux_pam, openpam) in codes {
+        let status = linux_pam::to_status(linux_pam).unwrap();
+        assert_eq!(openpam::from_status(status), Some(openpam), "{name}");
+        assert_eq!(openpam::to_status(openpam), Some(status), "{name}");
+    }
+}
+
+#[test]
+fn examples() {
+    assert_eq!(linux_pam::to_status(19), Some(PamStatus::ConvErr));
+    assert_eq!(openpam::from_status(PamStatus::ConvErr), Some(6));
+    assert_eq!(linux_pam::to_status(7), Some(PamStatus::AuthErr));
+    assert_eq!(openpam::from_status(PamStatus::AuthErr), Some(9));
+
+    assert_eq!(linux_pam::from_status(PamStatus::BadHandle), None);
+    assert_eq!(openpam::from_status(PamStatus::Incomplete), None);
+}
@@ -0,0 +1,53 @@
+//! Converts X authentication data from and to the `pam_xauth_data` passed to libpam.
+#![cfg(pam_impl = "linux-pam")]
+
+use std::ptr;
+
+use libpam_sys::linux_pam::pam_xauth_data;
+use libpam_sys::xauth::XAuth;
+
+const COOKIE: [u8; 16] = *b"\x00\x01\x02\x03cookie\x00\xff\xfe\xfd\x10\x20";
+
+#[test]
+fn round_trip() {
+    let xauth = XAuth::new(c"MIT-MAGIC-COOKIE-1", &COOKIE);
+
+    let raw = xauth.as_raw();
+    assert_eq!(raw.namelen, 18);
+    assert_eq!(raw.datalen, 16);
+
+    let copy = unsafe { XAuth::from_raw(&raw) }.unwrap();
+    assert_eq!(copy.name(), c"MIT-MAGIC-COOKIE-1");
+    assert_eq!(copy.data(), COOKIE);
+    assert_eq!(copy, xauth);
+}
+
+#[test]
+fn no_name() {
+    let raw = pam_xauth_data {
+        namelen: 0,
+        name: ptr::null_mut(),
+        datalen: 0,
+        data: ptr::null_mut(),
+    };
+
+    assert!(unsafe { XAuth::from_raw(&raw) }.is_none());
+}
+
+#[test]
+fn no_data() {
+    let mut raw = XAuth::new(c"MIT-MAGIC-COOKIE-1", &[]).as_raw();
+    raw.data = ptr::null_mut();
+
+    let xauth = unsafe { XAuth::from_raw(&raw) }.unwrap();
+    assert!(xauth.data().is_empty());
+}
+
+#[test]
+fn redacted() {
+    let xauth = XAuth::new(c"MIT-MAGIC-COOKIE-1", &COOKIE);
+    assert_eq!(
+        format!("{xauth:?}"),
+        r#"XAuth { name: "MIT-MAGIC-COOKIE-1", .. }"#
+    );
+}